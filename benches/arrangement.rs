@@ -0,0 +1,54 @@
+// Compares building a drawing from many random segments with repeated add_line against a single batch, which resolves all
+// intersections in one pass (see src/arrangement.rs). Run with `cargo bench`, or `cargo bench -- 2000` for another size.
+use std::time::Instant;
+use centroid_calculator::ShapeBuilder;
+
+// Small xorshift generator, so the benchmark is repeatable without extra dependencies
+struct Random(u64);
+
+impl Random {
+    // Uniform value in [0, 1)
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Short segments scattered over a 1000x1000 area, like the edges of an imported outline
+fn random_segments(count: usize) -> Vec<[f64; 4]> {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    (0..count).map(|_| {
+        let (x, y) = (random.next() * 1000.0, random.next() * 1000.0);
+        let angle = random.next() * std::f64::consts::TAU;
+        let length = 1.0 + random.next() * 19.0;
+        [x, y, x + length * angle.cos(), y + length * angle.sin()]
+    }).collect()
+}
+
+fn main() {
+    let count = std::env::args().skip(1).find_map(|arg| arg.parse().ok()).unwrap_or(10_000);
+    let segments = random_segments(count);
+
+    let start = Instant::now();
+    let mut sequential = ShapeBuilder::new();
+    for &[x1, y1, x2, y2] in &segments {
+        sequential.add_line(x1, y1, x2, y2).unwrap();
+    }
+    let sequential_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut batched = ShapeBuilder::new();
+    batched.begin_batch();
+    for &[x1, y1, x2, y2] in &segments {
+        batched.add_line(x1, y1, x2, y2).unwrap();
+    }
+    batched.commit_batch().unwrap();
+    let batch_time = start.elapsed();
+
+    println!("{} random segments", count);
+    println!("repeated add_line: {:>10.1?} ({} lines)", sequential_time, sequential.lines().len());
+    println!("batch:             {:>10.1?} ({} lines)", batch_time, batched.lines().len());
+    println!("speedup:           {:>10.1}x", sequential_time.as_secs_f64() / batch_time.as_secs_f64());
+}
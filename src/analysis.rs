@@ -0,0 +1,72 @@
+// Everything ShapeBuilder derives from its lines (loops, area, centroid and section properties), worked out on first use
+// and kept until the lines change.
+//
+// ShapeBuilder bumps its version whenever a line is inserted or removed, including by undo and redo. The cache remembers the
+// version it was computed for, so edits that don't change anything (a rejected line, deleting a line that isn't there)
+// keep the cached results, and repeated queries between edits cost nothing. Errors are cached as well.
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::error::ShapeError;
+use crate::loops::{self, Loop};
+use crate::section::SectionProperties;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Analysis {
+    pub loops: Rc<[Loop]>,
+    pub section: SectionProperties,
+}
+
+type Outcome = Result<Rc<Analysis>, ShapeError>;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Cache {
+    // The version the outcome was computed for, and the outcome
+    entry: RefCell<Option<(u32, Outcome)>>,
+}
+
+impl Cache {
+    // Results for the given lines and version, computed only if the version changed since last time
+    pub(crate) fn get(&self, lines: &[Vec<(f64, f64)>], version: u32) -> Outcome {
+        let mut entry = self.entry.borrow_mut();
+        match &*entry {
+            Some((built_for, result)) if *built_for == version => result.clone(),
+            _ => {
+                let result = analyze(lines).map(Rc::new);
+                *entry = Some((version, result.clone()));
+                result
+            }
+        }
+    }
+}
+
+fn analyze(lines: &[Vec<(f64, f64)>]) -> Result<Analysis, ShapeError> {
+    loops::check_lines(lines)?;
+    if lines.len() < 3 {
+        return Err(ShapeError::Degenerate); // Cannot enclose anything with less than 3 lines.
+    }
+
+    let found = loops::find_loops(lines);
+    if found.is_empty() {
+        return Err(ShapeError::NotClosed);
+    }
+
+    let (area, centroid) = loops::area_centroid(&found);
+    if area <= f64::EPSILON * extent_squared(lines) {
+        return Err(ShapeError::ZeroArea);
+    }
+    let section = SectionProperties::from_loops(&found, area, centroid);
+    Ok(Analysis { loops: found.into(), section })
+}
+
+// Square of the largest width or height of the drawing, the scale for "close enough to zero" area checks
+fn extent_squared(lines: &[Vec<(f64, f64)>]) -> f64 {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in lines.iter().flatten() {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let extent = (max_x - min_x).max(max_y - min_y);
+    extent * extent
+}
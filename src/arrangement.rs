@@ -0,0 +1,427 @@
+// Resolves many new lines against the drawing in one pass, instead of running add_line's intersection handling once per
+// line. The result is the same set of lines add_line would leave behind: collinear lines that overlap or touch are merged,
+// and every line is cut wherever another line crosses or touches it.
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::collinear_union;
+use crate::loops::point_key;
+
+pub(crate) type Point = (f64, f64);
+pub(crate) type Segment = (Point, Point);
+
+pub(crate) struct Resolved {
+    // Indices of existing lines that were merged or split, in ascending order
+    pub removed: Vec<usize>,
+    // Lines replacing them, followed by the pieces of the new lines
+    pub added: Vec<Vec<Point>>,
+}
+
+// How two segments meet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Meeting {
+    Apart,
+    // On the same infinite line, whether or not they overlap
+    Collinear,
+    At(Point),
+}
+
+// Where two segments meet, for resolve and get_intersection. A meeting at the end of either segment returns that endpoint
+// exactly, so lines ending on another line share a vertex instead of a nearly identical point.
+pub(crate) fn meet(a: Segment, b: Segment) -> Meeting {
+    let ((a1x, a1y), (a2x, a2y)) = a;
+    let ((b1x, b1y), (b2x, b2y)) = b;
+    let alpha_num = ((b2x - b1x) * (b1y - a1y)) - ((b2y - b1y) * (b1x - a1x));
+    let beta_num = ((a2x - a1x) * (b1y - a1y)) - ((a2y - a1y) * (b1x - a1x));
+    let denom = ((b2x - b1x) * (a2y - a1y)) - ((b2y - b1y) * (a2x - a1x));
+    if denom == 0.0 {
+        return if alpha_num == 0.0 { Meeting::Collinear } else { Meeting::Apart };
+    }
+    let alpha = alpha_num / denom;
+    let beta = beta_num / denom;
+    if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+        return Meeting::Apart;
+    }
+    let point = if alpha == 0.0 {
+        a.0
+    } else if alpha == 1.0 {
+        a.1
+    } else if beta == 0.0 {
+        b.0
+    } else if beta == 1.0 {
+        b.1
+    } else {
+        (a1x + alpha * (a2x - a1x), a1y + alpha * (a2y - a1y))
+    };
+    Meeting::At(point)
+}
+
+// Pairs of segments that meet at a point or lie on the same infinite line, where at least one of the two is at or after
+// first_new, found with a Bentley–Ottmann sweep.
+//
+// The sweep moves across the segments left to right, and bottom to top at the same x. Its events are the segment
+// endpoints and the crossings found so far. The active segments, those the sweep is currently passing through, are kept
+// ordered by their height where the sweep crosses them. Two segments can only meet once nothing lies between them in
+// that order, so only segments that become neighbours are tested with meet, and a crossing found ahead is queued as an
+// event where the two swap places. All segments at an event point are tested against each other and put back in the
+// order they leave it, so lines meeting at a shared vertex or at a T are found too. This takes O((n + k) log n) for n
+// segments and k meetings, apart from shifting the active list on inserts, which is cheap memory movement even for
+// thousands of active segments.
+pub(crate) fn touching_pairs(segments: &[Segment], first_new: usize) -> Vec<(usize, usize, Meeting)> {
+    Sweep::new(segments, first_new).run()
+}
+
+// Relative difference in height below which a segment is taken to pass through an event point
+const HEIGHT_TOLERANCE: f64 = 1e-12;
+
+// A point of the sweep, ordered left to right and then bottom to top
+#[derive(Clone, Copy, Debug)]
+struct SweepPoint(Point);
+
+impl SweepPoint {
+    // Folds -0.0 into 0.0 so equal points compare equal
+    fn new(point: Point) -> SweepPoint {
+        SweepPoint((point.0 + 0.0, point.1 + 0.0))
+    }
+}
+
+impl Ord for SweepPoint {
+    fn cmp(&self, other: &SweepPoint) -> Ordering {
+        self.0 .0.total_cmp(&other.0 .0).then(self.0 .1.total_cmp(&other.0 .1))
+    }
+}
+
+impl PartialOrd for SweepPoint {
+    fn partial_cmp(&self, other: &SweepPoint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SweepPoint {
+    fn eq(&self, other: &SweepPoint) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SweepPoint {}
+
+struct Sweep<'a> {
+    segments: &'a [Segment],
+    first_new: usize,
+    // Each segment's endpoints in sweep order, and its slope (infinite for vertical segments)
+    left: Vec<Point>,
+    right: Vec<Point>,
+    slope: Vec<f64>,
+    // Segment endpoints in sweep order, each with its segment and whether the segment starts there
+    endpoints: Vec<(SweepPoint, usize, bool)>,
+    // Crossings found ahead of the sweep, with the segments that swap places there
+    crossings: BTreeMap<SweepPoint, Vec<usize>>,
+    // Active segments, lowest first
+    status: Vec<usize>,
+    active: Vec<bool>,
+    // Segments passing through the current event point
+    block: Vec<usize>,
+    // Pairs already reported, and pairs whose crossing is already queued
+    reported: HashSet<(usize, usize)>,
+    queued: HashSet<(usize, usize)>,
+    pairs: Vec<(usize, usize, Meeting)>,
+}
+
+impl<'a> Sweep<'a> {
+    fn new(segments: &'a [Segment], first_new: usize) -> Sweep<'a> {
+        let mut sweep = Sweep {
+            segments,
+            first_new,
+            left: Vec::with_capacity(segments.len()),
+            right: Vec::with_capacity(segments.len()),
+            slope: Vec::with_capacity(segments.len()),
+            endpoints: Vec::with_capacity(2 * segments.len()),
+            crossings: BTreeMap::new(),
+            status: Vec::new(),
+            active: vec![false; segments.len()],
+            block: Vec::new(),
+            reported: HashSet::new(),
+            queued: HashSet::new(),
+            pairs: Vec::new(),
+        };
+        for (s, &(a, b)) in segments.iter().enumerate() {
+            let (a, b) = (SweepPoint::new(a), SweepPoint::new(b));
+            let (left, right) = if a <= b { (a, b) } else { (b, a) };
+            let (dx, dy) = (right.0 .0 - left.0 .0, right.0 .1 - left.0 .1);
+            sweep.left.push(left.0);
+            sweep.right.push(right.0);
+            sweep.slope.push(if dx == 0.0 { f64::INFINITY } else { dy / dx });
+            // A segment of zero length never meets anything at a point, so it takes no part
+            if left != right {
+                sweep.endpoints.push((left, s, true));
+                sweep.endpoints.push((right, s, false));
+            }
+        }
+        sweep.endpoints.sort_unstable_by_key(|endpoint| endpoint.0);
+        sweep
+    }
+
+    fn run(mut self) -> Vec<(usize, usize, Meeting)> {
+        let (mut starts, mut ends) = (Vec::new(), Vec::new());
+        let mut next = 0;
+        loop {
+            let endpoint = self.endpoints.get(next).map(|&(point, _, _)| point);
+            let crossing = self.crossings.first_key_value().map(|(&point, _)| point);
+            let point = match (endpoint, crossing) {
+                (Some(endpoint), Some(crossing)) => endpoint.min(crossing),
+                (Some(point), None) | (None, Some(point)) => point,
+                (None, None) => break,
+            };
+            starts.clear();
+            ends.clear();
+            while let Some(&(at, s, starting)) = self.endpoints.get(next) {
+                if at != point {
+                    break;
+                }
+                if starting { starts.push(s) } else { ends.push(s) }
+                next += 1;
+            }
+            let crossing = if crossing == Some(point) { self.crossings.pop_first().map(|(_, crossing)| crossing) } else { None };
+            self.event(point, &starts, &ends, crossing.as_deref().unwrap_or_default());
+        }
+        self.pairs
+    }
+
+    fn event(&mut self, point: SweepPoint, starts: &[usize], ends: &[usize], crossing: &[usize]) {
+        let p = point.0;
+
+        // The active segments passing through p: those ending or crossing here, everything between them, and anything
+        // else at p's height
+        let (mut low, mut high) = (usize::MAX, 0);
+        for &s in ends.iter().chain(crossing) {
+            if self.active[s] {
+                let position = self.locate(s, p);
+                low = low.min(position);
+                high = high.max(position + 1);
+            }
+        }
+        if low == usize::MAX {
+            low = self.status.partition_point(|&t| self.height(t, p) < p.1);
+            high = low;
+        }
+        while low > 0 && self.at_height(self.status[low - 1], p) {
+            low -= 1;
+        }
+        while high < self.status.len() && self.at_height(self.status[high], p) {
+            high += 1;
+        }
+
+        self.block.clear();
+        self.block.extend(self.status.drain(low..high));
+        self.block.extend(starts);
+        for k in 0..self.block.len() {
+            for m in k + 1..self.block.len() {
+                self.test(self.block[k], self.block[m]);
+            }
+        }
+        for &s in starts {
+            self.active[s] = true;
+        }
+        for &s in ends {
+            self.active[s] = false;
+        }
+
+        // Leaving p, the segments that carry on are ordered by slope
+        let (active, slope) = (&self.active, &self.slope);
+        self.block.retain(|&s| active[s]);
+        self.block.sort_unstable_by(|&a, &b| slope[a].total_cmp(&slope[b]).then(a.cmp(&b)));
+        let count = self.block.len();
+        self.status.splice(low..low, self.block.iter().copied());
+
+        // Test the new neighbours
+        let first = low.saturating_sub(1);
+        let last = (low + count + 1).min(self.status.len());
+        for k in first..last.saturating_sub(1) {
+            self.neighbours(self.status[k], self.status[k + 1], point);
+        }
+    }
+
+    // Height of an active segment where the sweep crosses it at p. A vertical segment is crossed at p itself.
+    fn height(&self, s: usize, p: Point) -> f64 {
+        let (left, right) = (self.left[s], self.right[s]);
+        if left.0 == right.0 {
+            p.1.clamp(left.1, right.1)
+        } else if p.0 <= left.0 {
+            left.1
+        } else if p.0 >= right.0 {
+            right.1
+        } else {
+            left.1 + (p.0 - left.0) * self.slope[s]
+        }
+    }
+
+    // Whether an active segment passes through p, allowing for the rounding in its height. Taking in a segment that only
+    // passes close by is harmless, as every pair is still tested exactly with meet.
+    fn at_height(&self, s: usize, p: Point) -> bool {
+        let height = self.height(s, p);
+        (height - p.1).abs() <= HEIGHT_TOLERANCE * height.abs().max(p.1.abs()).max(p.0.abs())
+    }
+
+    // Position of an active segment in the status. Rounding can leave the status slightly out of order, so the search
+    // starts where the segment's height puts it and works outwards.
+    fn locate(&self, s: usize, p: Point) -> usize {
+        let height = self.height(s, p);
+        let guess = self.status.partition_point(|&t| self.height(t, p) < height);
+        (0..self.status.len())
+            .flat_map(|offset| [guess.checked_add(offset), guess.checked_sub(offset + 1)])
+            .flatten()
+            .find(|&k| self.status.get(k) == Some(&s))
+            .expect("active segment is in the status")
+    }
+
+    // Reports how two segments meet, unless they are apart or already reported
+    fn test(&mut self, a: usize, b: usize) -> Meeting {
+        let (i, j) = (a.min(b), a.max(b));
+        let meeting = meet(self.segments[i], self.segments[j]);
+        if j >= self.first_new && !matches!(meeting, Meeting::Apart) && self.reported.insert((i, j)) {
+            self.pairs.push((i, j, meeting));
+        }
+        meeting
+    }
+
+    // Tests segments that have just become neighbours, `below` under `above`. If they cross further on, they swap places
+    // there. A crossing rounded to a point the sweep has already passed is handled at the current point instead.
+    fn neighbours(&mut self, below: usize, above: usize, point: SweepPoint) {
+        let Meeting::At(at) = self.test(below, above) else { return };
+        let carry_on = self.right[below] != at && self.right[above] != at;
+        if carry_on && self.slope[below] > self.slope[above] && self.queued.insert((below.min(above), below.max(above))) {
+            // Rounding can put the crossing just beside one of the segments. A vertical segment is only active at its own
+            // x, so the crossing is kept within the x range both segments share.
+            let (low, high) = (self.left[below].0.max(self.left[above].0), self.right[below].0.min(self.right[above].0));
+            let at = if low <= high { (at.0.clamp(low, high), at.1) } else { at };
+            let at = SweepPoint::new(at).max(point);
+            self.crossings.entry(at).or_default().extend([below, above]);
+        }
+    }
+}
+
+pub(crate) fn boxes_touch(a: Segment, b: Segment) -> bool {
+    a.0 .0.min(a.1 .0) <= b.0 .0.max(b.1 .0) && b.0 .0.min(b.1 .0) <= a.0 .0.max(a.1 .0)
+        && a.0 .1.min(a.1 .1) <= b.0 .1.max(b.1 .1) && b.0 .1.min(b.1 .1) <= a.0 .1.max(a.1 .1)
+}
+
+fn find_root(parent: &mut [usize], mut v: usize) -> usize {
+    while parent[v] != v {
+        parent[v] = parent[parent[v]];
+        v = parent[v];
+    }
+    v
+}
+
+// Key that is the same for a line in either direction
+fn line_key(a: Point, b: Point) -> ((u64, u64), (u64, u64)) {
+    let (ka, kb) = (point_key(a), point_key(b));
+    if ka <= kb { (ka, kb) } else { (kb, ka) }
+}
+
+pub(crate) fn resolve(existing: &[Vec<Point>], new: Vec<Vec<Point>>) -> Resolved {
+    let first_new = existing.len();
+    let segments: Vec<Segment> = existing.iter().chain(new.iter()).map(|line| (line[0], line[1])).collect();
+
+    // Step 1: merge collinear lines that overlap or touch. Existing lines were already merged with each other, so only
+    // pairs involving a new line need checking. Chains of overlapping lines end up in the same group.
+    let mut parent: Vec<usize> = (0..segments.len()).collect();
+    for (i, j, meeting) in touching_pairs(&segments, first_new) {
+        let (a, b) = (segments[i], segments[j]);
+        if meeting == Meeting::Collinear && collinear_union(a.0, a.1, b.0, b.1).is_some() {
+            let (root_i, root_j) = (find_root(&mut parent, i), find_root(&mut parent, j));
+            parent[root_i] = root_j;
+        }
+    }
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..segments.len() {
+        let root = find_root(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut removed: Vec<usize> = Vec::new();
+    let mut kept: Vec<usize> = Vec::new(); // Existing lines that survive step 1 unchanged
+    let mut merged: Vec<Segment> = Vec::new(); // New lines, including merged groups
+    let mut members: Vec<&Vec<usize>> = groups.values().collect();
+    members.sort_by_key(|group| group[0]);
+    for group in members {
+        if group.len() == 1 {
+            if group[0] < first_new {
+                kept.push(group[0]);
+            } else {
+                merged.push(segments[group[0]]);
+            }
+            continue;
+        }
+        // The merged line runs between the two endpoints furthest apart along the group's direction
+        let (start, end) = segments[group[0]];
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let along = |p: Point| (p.0 - start.0) * dx + (p.1 - start.1) * dy;
+        let mut low = start;
+        let mut high = end;
+        for &i in group {
+            for p in [segments[i].0, segments[i].1] {
+                if along(p) < along(low) {
+                    low = p;
+                }
+                if along(p) > along(high) {
+                    high = p;
+                }
+            }
+            if i < first_new {
+                removed.push(i);
+            }
+        }
+        merged.push((low, high));
+    }
+    kept.sort_unstable();
+
+    // Step 2: cut every line where another one meets it. Again only pairs involving a new line can meet.
+    let working: Vec<Segment> = kept.iter().map(|&i| segments[i]).chain(merged.iter().copied()).collect();
+    let first_merged = kept.len();
+    let mut cuts: Vec<Vec<Point>> = vec![Vec::new(); working.len()];
+    for (i, j, meeting) in touching_pairs(&working, first_merged) {
+        if let Meeting::At(point) = meeting {
+            cuts[i].push(point);
+            cuts[j].push(point);
+        }
+    }
+
+    let mut pieces: Vec<Segment> = Vec::new();
+    for (w, segment) in working.iter().enumerate() {
+        let cut_inside = cuts[w].iter().any(|&p| p != segment.0 && p != segment.1);
+        if w < first_merged {
+            // An existing line is only replaced if something cuts it away from its endpoints
+            if !cut_inside {
+                continue;
+            }
+            removed.push(kept[w]);
+        }
+        pieces.extend(split_segment(*segment, &mut cuts[w]));
+    }
+    removed.sort_unstable();
+    removed.dedup();
+
+    // Skip points and lines that are already in the drawing
+    let removed_set: HashSet<usize> = removed.iter().copied().collect();
+    let mut seen: HashSet<((u64, u64), (u64, u64))> = existing.iter().enumerate()
+        .filter(|(i, _)| !removed_set.contains(i))
+        .map(|(_, line)| line_key(line[0], line[1]))
+        .collect();
+    let added = pieces.into_iter()
+        .filter(|&(a, b)| a != b && seen.insert(line_key(a, b)))
+        .map(|(a, b)| vec![a, b])
+        .collect();
+
+    Resolved { removed, added }
+}
+
+// Pieces of a segment between consecutive cut points, in the segment's direction
+pub(crate) fn split_segment(segment: Segment, cuts: &mut Vec<Point>) -> Vec<Segment> {
+    let (start, end) = segment;
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    cuts.push(start);
+    cuts.push(end);
+    cuts.sort_by(|a, b| ((a.0 - start.0) * dx + (a.1 - start.1) * dy).total_cmp(&((b.0 - start.0) * dx + (b.1 - start.1) * dy)));
+    cuts.dedup();
+    cuts.windows(2).map(|pair| (pair[0], pair[1])).filter(|(a, b)| a != b).collect()
+}
@@ -0,0 +1,35 @@
+// Lines queued between ShapeBuilder::begin_batch and ShapeBuilder::commit_batch.
+//
+// Queued lines are not split or merged until the batch is committed, when arrangement::resolve handles all of them in one
+// pass. The batch is also a single undo step, and it is atomic: once any edit in it fails, committing reverts the whole
+// batch and returns the first error.
+use crate::ShapeError;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Batch {
+    pub pending: Vec<Vec<(f64, f64)>>,
+    pub error: Option<ShapeError>,
+}
+
+impl Batch {
+    // Queues a line, unless an earlier failure already spoiled the batch
+    pub(crate) fn queue(&mut self, line: Vec<(f64, f64)>) -> Result<(), ShapeError> {
+        match &self.error {
+            Some(error) => Err(error.clone()),
+            None => {
+                self.pending.push(line);
+                Ok(())
+            }
+        }
+    }
+
+    // Marks the batch as failed. Only the first error is kept.
+    pub(crate) fn fail(&mut self, error: ShapeError) {
+        self.error.get_or_insert(error);
+    }
+
+    // Forgets queued lines matching the given line in either direction
+    pub(crate) fn remove(&mut self, line_a: &[(f64, f64)], line_b: &[(f64, f64)]) {
+        self.pending.retain(|line| line != line_a && line != line_b);
+    }
+}
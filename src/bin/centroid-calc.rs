@@ -0,0 +1,235 @@
+// centroid-calc: prints the centroid and section properties of drawing files, so section checks can be scripted.
+//
+//     centroid-calc [--format table|json|csv] [--tolerance T] [--layer NAME]... FILE...
+//
+// The file type comes from the extension: .json (saved drawings), .svg, .dxf, .csv/.txt/.xy/.xyz (coordinate lists),
+// .wkt and .geojson. Files that can't be read or don't enclose anything are reported on stderr and the rest are still
+// printed; the exit status is 1 if any file failed and 2 for bad arguments.
+use std::path::Path;
+use std::process::ExitCode;
+use serde_json::json;
+use centroid_calculator::{decimals, SectionProperties, ShapeBuilder};
+
+const USAGE: &str = "\
+Usage: centroid-calc [OPTIONS] FILE...
+
+Prints the area, centroid and section properties of each drawing.
+
+Files: .json (saved drawings), .svg, .dxf, .csv/.txt/.xy/.xyz (coordinate lists), .wkt, .geojson
+
+Options:
+  -f, --format FORMAT    table (default), json or csv
+  -t, --tolerance T      How far curves in SVG and DXF files may be straightened, in drawing units (default 0.01)
+  -l, --layer NAME       Only read this DXF layer (repeat for more; default all layers)
+  -h, --help             Show this help
+  -V, --version          Show the version";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+struct Options {
+    format: Format,
+    tolerance: f64,
+    layers: Vec<String>,
+    files: Vec<String>,
+}
+
+// One file's results
+struct Row {
+    file: String,
+    units: String,
+    section: SectionProperties,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("centroid-calc: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut rows = Vec::new();
+    let mut failed = false;
+    for file in &options.files {
+        match load(file, &options).and_then(|shape| {
+            let section = shape.section_properties().map_err(|error| error.to_string())?;
+            Ok(Row { file: file.clone(), units: shape.units(), section })
+        }) {
+            Ok(row) => rows.push(row),
+            Err(message) => {
+                eprintln!("centroid-calc: {}: {}", file, message);
+                failed = true;
+            }
+        }
+    }
+
+    print!("{}", match options.format {
+        Format::Table => table(&rows),
+        Format::Json => json(&rows),
+        Format::Csv => csv(&rows),
+    });
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+// The options, or None if only help or the version was asked for
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options { format: Format::Table, tolerance: 0.01, layers: Vec::new(), files: Vec::new() };
+    while let Some(arg) = args.next() {
+        // --name=value is the same as --name value
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || inline.clone().or_else(|| args.next()).ok_or_else(|| format!("{} needs a value", name));
+        match name.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(None);
+            }
+            "-V" | "--version" => {
+                println!("centroid-calc {}", env!("CARGO_PKG_VERSION"));
+                return Ok(None);
+            }
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    other => return Err(format!("unknown format \"{}\", expected table, json or csv", other)),
+                }
+            }
+            "-t" | "--tolerance" => {
+                let text = value()?;
+                options.tolerance = text.parse().ok().filter(|t: &f64| t.is_finite() && *t > 0.0)
+                    .ok_or_else(|| format!("the tolerance must be a positive number, not \"{}\"", text))?;
+            }
+            "-l" | "--layer" => options.layers.push(value()?),
+            "--" => options.files.extend(args.by_ref()),
+            _ if name.starts_with('-') && name.len() > 1 => return Err(format!("unknown option {}", name)),
+            _ => options.files.push(arg),
+        }
+    }
+    if options.files.is_empty() {
+        return Err("no files given".to_string());
+    }
+    Ok(Some(options))
+}
+
+// Reads a drawing, choosing the reader by the file's extension
+fn load(file: &str, options: &Options) -> Result<ShapeBuilder, String> {
+    let text = std::fs::read_to_string(file).map_err(|error| error.to_string())?;
+    let extension = Path::new(file).extension().and_then(|extension| extension.to_str()).unwrap_or("").to_ascii_lowercase();
+    let mut shape = ShapeBuilder::new();
+    let result = match extension.as_str() {
+        "json" => ShapeBuilder::from_json(&text).map(|opened| shape = opened),
+        "svg" => shape.import_svg(&text, options.tolerance),
+        "dxf" => shape.import_dxf(&text, options.layers.clone(), options.tolerance),
+        "csv" | "txt" | "xy" | "xyz" => shape.import_csv(&text),
+        "wkt" => shape.import_wkt(&text),
+        "geojson" => shape.import_geojson(&text),
+        _ => return Err(format!("don't know how to read \"{}\" files", extension)),
+    };
+    result.map_err(|error| error.to_string())?;
+    Ok(shape)
+}
+
+// CSV column names, matching the file, units and numbers()
+const CSV_HEADER: [&str; 11] = [
+    "file", "units", "area", "centroid_x", "centroid_y", "ixx", "iyy", "ixy", "i_major", "i_minor", "principal_angle_deg",
+];
+
+// The numbers of a row, in the order of the columns after the file and units
+fn numbers(section: &SectionProperties) -> [f64; 9] {
+    [
+        section.area,
+        section.centroid.0,
+        section.centroid.1,
+        section.ixx,
+        section.iyy,
+        section.ixy,
+        section.i_major,
+        section.i_minor,
+        section.principal_angle.to_degrees(),
+    ]
+}
+
+// Aligned columns for reading, with numbers rounded to six significant figures
+fn table(rows: &[Row]) -> String {
+    let headers = ["file", "units", "area", "x", "y", "Ixx", "Iyy", "Ixy", "I major", "I minor", "angle (deg)"];
+    let lines: Vec<Vec<String>> = std::iter::once(headers.map(str::to_string).to_vec())
+        .chain(rows.iter().map(|row| {
+            [row.file.clone(), row.units.clone()].into_iter().chain(numbers(&row.section).map(round)).collect()
+        }))
+        .collect();
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| lines.iter().map(|line| line[i].chars().count()).max().unwrap_or(0))
+        .collect();
+    let mut out = String::new();
+    for line in &lines {
+        let padded: Vec<String> = line.iter().enumerate().map(|(i, cell)| {
+            // Text on the left, numbers on the right
+            if i < 2 { format!("{:<width$}", cell, width = widths[i]) } else { format!("{:>width$}", cell, width = widths[i]) }
+        }).collect();
+        out.push_str(padded.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+// A JSON array with an object per file, numbers at full precision
+fn json(rows: &[Row]) -> String {
+    let objects: Vec<serde_json::Value> = rows.iter().map(|row| {
+        let section = &row.section;
+        json!({
+            "file": row.file,
+            "units": row.units,
+            "area": section.area,
+            "centroid": [section.centroid.0, section.centroid.1],
+            "ixx": section.ixx,
+            "iyy": section.iyy,
+            "ixy": section.ixy,
+            "i_major": section.i_major,
+            "i_minor": section.i_minor,
+            "principal_angle_deg": section.principal_angle.to_degrees(),
+        })
+    }).collect();
+    let mut out = serde_json::to_string_pretty(&objects).expect("results only hold strings and finite numbers");
+    out.push('\n');
+    out
+}
+
+// A header line and a line per file, numbers at full precision
+fn csv(rows: &[Row]) -> String {
+    let mut out = CSV_HEADER.join(",") + "\n";
+    for row in rows {
+        let numbers = numbers(&row.section).map(|value| value.to_string());
+        let values: Vec<String> = [quote(&row.file), quote(&row.units)].into_iter().chain(numbers).collect();
+        out.push_str(&values.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// A CSV field, quoted if it holds a delimiter, quote or line break
+fn quote(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// A number to six significant figures, without trailing zeros
+fn round(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format!("{}", value + 0.0);
+    }
+    decimals(value, (5 - value.abs().log10().floor() as i32).max(0) as usize)
+}
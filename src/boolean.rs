@@ -0,0 +1,139 @@
+// Boolean operations between the regions enclosed by two drawings, for ShapeBuilder::union, difference, intersection and
+// xor.
+//
+// Both drawings' lines are overlaid and cut wherever they cross, as add_line would. Each piece of the overlay has the
+// same face on either side all along it, so a point just to its left and one just to its right tell which side is in the
+// result. A piece is on the result's boundary when the operation keeps one side and not the other, and the pieces on the
+// boundary, outlines and holes alike, are the result's lines.
+use crate::arrangement;
+use crate::error::ShapeError;
+use crate::grid::{self, Grid};
+use crate::loops::{self, Loop};
+
+type Point = (f64, f64);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Operation {
+    Union,
+    Difference,
+    Intersection,
+    Xor,
+}
+
+impl Operation {
+    // Whether a point is in the result, given whether it's in the first and second regions
+    fn keeps(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            Operation::Union => in_a || in_b,
+            Operation::Difference => in_a && !in_b,
+            Operation::Intersection => in_a && in_b,
+            Operation::Xor => in_a != in_b,
+        }
+    }
+}
+
+// A region as polygons with their bounding boxes: either the loops of a drawing, or polygons that are each solid
+struct Region {
+    polygons: Vec<(Vec<Point>, Point, Point)>,
+    // Whether a point in any polygon is in the region, rather than one in an odd number of them
+    any: bool,
+}
+
+impl Region {
+    // Loops at odd depths add material and loops at even depths cut it away, and every loop around a point is one level
+    // deeper than the last, so a point is solid when an odd number of loops contain it
+    fn new(lines: &[Vec<Point>]) -> Region {
+        Region::with_polygons(loops::find_loops(lines).into_iter().map(|found: Loop| found.points), false)
+    }
+
+    // The union of the polygons, however they overlap
+    fn union_of(polygons: &[Vec<Point>]) -> Region {
+        Region::with_polygons(polygons.iter().cloned(), true)
+    }
+
+    fn with_polygons(polygons: impl Iterator<Item = Vec<Point>>, any: bool) -> Region {
+        let polygons = polygons.map(|points| {
+            let min = points.iter().fold((f64::INFINITY, f64::INFINITY), |min, p| (min.0.min(p.0), min.1.min(p.1)));
+            let max = points.iter().fold((f64::NEG_INFINITY, f64::NEG_INFINITY), |max, p| (max.0.max(p.0), max.1.max(p.1)));
+            (points, min, max)
+        }).collect();
+        Region { polygons, any }
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        let mut around = self.polygons.iter()
+            .filter(|(_, min, max)| min.0 <= point.0 && point.0 <= max.0 && min.1 <= point.1 && point.1 <= max.1)
+            .filter(|(points, _, _)| loops::point_in_polygon(point, points));
+        if self.any { around.next().is_some() } else { around.count() % 2 == 1 }
+    }
+}
+
+// Lines of the region the operation gives from the regions enclosed by a and b. Lines that don't enclose anything in
+// either drawing play no part and are dropped. TouchingHole if the result can't be drawn, e.g. the XOR of two overlapping
+// squares, whose middle is a hole cornered by the two pieces.
+pub(crate) fn combine(a: &[Vec<Point>], b: &[Vec<Point>], operation: Operation) -> Result<Vec<Vec<Point>>, ShapeError> {
+    loops::check_lines(a)?;
+    loops::check_lines(b)?;
+    overlay_regions(a, b.to_vec(), Region::new(a), Region::new(b), operation)
+}
+
+// Like combine, with the union of the polygons in place of the second drawing. The polygons may overlap each other.
+pub(crate) fn combine_polygons(a: &[Vec<Point>], polygons: &[Vec<Point>], operation: Operation) -> Result<Vec<Vec<Point>>, ShapeError> {
+    loops::check_lines(a)?;
+    let edges = polygons.iter()
+        .flat_map(|points| (0..points.len()).map(|i| vec![points[i], points[(i + 1) % points.len()]]))
+        .filter(|line| line[0] != line[1])
+        .collect();
+    overlay_regions(a, edges, Region::new(a), Region::union_of(polygons), operation)
+}
+
+// Overlays the lines of two regions and keeps the pieces on the boundary of the result
+fn overlay_regions(a: &[Vec<Point>], b: Vec<Vec<Point>>, region_a: Region, region_b: Region, operation: Operation) -> Result<Vec<Vec<Point>>, ShapeError> {
+    let overlay = arrangement::resolve(&[], a.iter().cloned().chain(b).collect()).added;
+    let mut grid = Grid::default();
+    for index in 0..overlay.len() {
+        grid.insert(&overlay[..=index], index);
+    }
+
+    // Each boundary piece, with points just to its left and right and whether the left one is in the result
+    let mut boundary = Vec::new();
+    for (index, line) in overlay.iter().enumerate() {
+        let Some((left, right)) = sides(&overlay, &grid, index) else { continue };
+        let keep_left = operation.keeps(region_a.contains(left), region_b.contains(left));
+        let keep_right = operation.keeps(region_a.contains(right), region_b.contains(right));
+        if keep_left != keep_right {
+            boundary.push((line.clone(), left, keep_left));
+        }
+    }
+
+    // Pieces of the same line left whole again by the pieces dropped between them are merged
+    let lines = arrangement::resolve(&[], boundary.iter().map(|(line, _, _)| line.clone()).collect()).added;
+    let result = Region::new(&lines);
+    if boundary.iter().any(|&(_, left, keep_left)| result.contains(left) != keep_left) {
+        return Err(ShapeError::TouchingHole);
+    }
+    Ok(lines)
+}
+
+// Points beside the middle of the line at index, to its left and right, closer to it than to any other line, so each is
+// in the face on its side. None for a line of zero length.
+fn sides(lines: &[Vec<Point>], grid: &Grid, index: usize) -> Option<(Point, Point)> {
+    let (start, end) = (lines[index][0], lines[index][1]);
+    let length = (end.0 - start.0).hypot(end.1 - start.1);
+    if length == 0.0 {
+        return None;
+    }
+    let middle = ((start.0 + end.0) / 2.0, (start.1 + end.1) / 2.0);
+    let mut step = length / 4.0;
+    for other in grid.near_box((middle.0 - step, middle.1 - step), (middle.0 + step, middle.1 + step)) {
+        if other != index {
+            let (distance, _) = grid::segment_distance(middle, lines[other][0], lines[other][1]);
+            step = step.min(distance / 2.0);
+        }
+    }
+    if step <= 0.0 {
+        return None;
+    }
+    let normal = (-(end.1 - start.1) / length * step, (end.0 - start.0) / length * step);
+    Some(((middle.0 + normal.0, middle.1 + normal.1), (middle.0 - normal.0, middle.1 - normal.1)))
+}
@@ -0,0 +1,30 @@
+// Flat copies of the stored lines for Javascript, which reads them straight out of Wasm memory as typed arrays instead of
+// converting nested arrays through serde-wasm-bindgen.
+//
+// Vertices are listed once each, in vertex ID order (see ShapeBuilder::vertices), and edges refer to them by vertex ID,
+// in edge ID order. The buffers are only rebuilt when the lines have changed since they were last built.
+use crate::vertices::Vertices;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Packed {
+    // ShapeBuilder version the buffers were built for
+    built_for: Option<u32>,
+    // x and y of each vertex: [x0, y0, x1, y1, ...]
+    pub coordinates: Vec<f64>,
+    // Vertex IDs of the two ends of each edge: [start0, end0, start1, end1, ...]
+    pub edges: Vec<u32>,
+}
+
+impl Packed {
+    pub(crate) fn update(&mut self, lines: &[Vec<(f64, f64)>], vertices: &Vertices, version: u32) {
+        if self.built_for == Some(version) {
+            return;
+        }
+        self.coordinates.clear();
+        self.coordinates.extend(vertices.points().iter().flat_map(|&(x, y)| [x, y]));
+        self.edges.clear();
+        // Every stored endpoint is in the index, since the lines and vertices change together
+        self.edges.extend(lines.iter().flatten().filter_map(|&point| vertices.id(point)).map(|id| id as u32));
+        self.built_for = Some(version);
+    }
+}
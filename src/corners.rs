@@ -0,0 +1,69 @@
+// Rounded and bevelled corners, for ShapeBuilder::fillet and ShapeBuilder::chamfer. Both cut the corner at v between the
+// edges v -> a and v -> b back to a point on each edge and join the two points: with an arc tangent to both edges, or
+// with a straight bevel. Each returns the new outline from a round to b, or None if the cut doesn't fit on the edges or
+// there's no corner to cut (the edges run straight on).
+use crate::curves;
+
+type Point = (f64, f64);
+
+// The two edges meeting at a corner (lower ID first), and the corner followed by the edges' far ends
+pub(crate) type Corner = ([usize; 2], [Point; 3]);
+
+// How far the fillet's straight pieces may stray from the true arc, as a fraction of its radius
+const FILLET_TOLERANCE: f64 = 1e-4;
+
+pub(crate) fn fillet(v: Point, a: Point, b: Point, radius: f64) -> Option<Vec<Point>> {
+    let (u1, length1) = unit(v, a);
+    let (u2, length2) = unit(v, b);
+    // Angle between the edges, and how far from the corner the arc touches them
+    let angle = cross(u1, u2).abs().atan2(dot(u1, u2));
+    if angle <= 0.0 || angle >= std::f64::consts::PI {
+        return None;
+    }
+    let reach = radius / (angle / 2.0).tan();
+    let (p1, p2) = (along(v, u1, reach, a, length1)?, along(v, u2, reach, b, length2)?);
+    // The arc turns from p1 to p2 through the corner's outside angle, with its center inside the corner: clockwise when
+    // b is counter-clockwise of a as seen from v, and the other way round
+    let sweep = (std::f64::consts::PI - angle).copysign(-cross(u1, u2));
+    let mut outline = vec![a, p1];
+    outline.extend(curves::arc_through(p1, p2, sweep, radius * FILLET_TOLERANCE));
+    outline.push(b);
+    outline.dedup();
+    Some(outline)
+}
+
+pub(crate) fn chamfer(v: Point, a: Point, b: Point, d1: f64, d2: f64) -> Option<Vec<Point>> {
+    let (u1, length1) = unit(v, a);
+    let (u2, length2) = unit(v, b);
+    if cross(u1, u2) == 0.0 {
+        return None;
+    }
+    let (p1, p2) = (along(v, u1, d1, a, length1)?, along(v, u2, d2, b, length2)?);
+    let mut outline = vec![a, p1, p2, b];
+    outline.dedup();
+    Some(outline)
+}
+
+fn unit(from: Point, to: Point) -> (Point, f64) {
+    let length = (to.0 - from.0).hypot(to.1 - from.1);
+    (((to.0 - from.0) / length, (to.1 - from.1) / length), length)
+}
+
+// The point distance along the edge from v in direction u, or the far end itself when the distance is the whole edge
+fn along(v: Point, u: Point, distance: f64, end: Point, length: f64) -> Option<Point> {
+    if distance > length {
+        None
+    } else if distance == length {
+        Some(end)
+    } else {
+        Some((v.0 + distance * u.0, v.1 + distance * u.1))
+    }
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.0 * b.0 + a.1 * b.1
+}
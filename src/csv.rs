@@ -0,0 +1,133 @@
+// Plain-text coordinate lists for ShapeBuilder::import_csv: one vertex per line, with blank lines between rings, as lab
+// and survey teams export them. Each ring is closed into a loop.
+//
+// The reader is tolerant of the usual variations:
+// - The delimiter is found from the first line: a tab, a semicolon, a comma, or else whitespace. With semicolons, a comma
+//   inside a number is a decimal comma ("1,5;2,25").
+// - A first line that isn't numbers is a header. If it names x and y columns (x/y, easting/northing or e/n), those are
+//   read; otherwise the first two columns are, and any further columns (z, codes) are ignored.
+// - Lines starting with #, // or % are comments.
+use crate::error::ShapeError;
+
+type Point = (f64, f64);
+
+// Segments, as [x1, y1, x2, y2], of the closed rings in the text
+pub(crate) fn parse(text: &str) -> Result<Vec<[f64; 4]>, ShapeError> {
+    let mut delimiter: Option<Delimiter> = None;
+    let mut columns = (0, 1);
+    let mut seen_data = false;
+    let mut rings: Vec<(usize, Vec<Point>)> = Vec::new();
+    let mut ring: Vec<Point> = Vec::new();
+    let mut ring_start = 0;
+
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.starts_with('#') || line.starts_with("//") || line.starts_with('%') {
+            continue;
+        }
+        let fields = if line.is_empty() { Vec::new() } else { delimiter.get_or_insert_with(|| Delimiter::detect(line)).split(line) };
+        if fields.iter().all(|field| field.is_empty()) {
+            // A blank line ends the ring
+            if !ring.is_empty() {
+                rings.push((ring_start, std::mem::take(&mut ring)));
+            }
+            continue;
+        }
+
+        let delimiter = delimiter.unwrap_or(Delimiter::Whitespace);
+        let values: Vec<Option<f64>> = fields.iter().map(|field| delimiter.number(field)).collect();
+        if !seen_data && values.iter().all(Option::is_none) {
+            columns = header_columns(&fields).unwrap_or(columns);
+            seen_data = true;
+            continue;
+        }
+        seen_data = true;
+        let column = |i: usize| -> Result<f64, ShapeError> {
+            match fields.get(i) {
+                None => Err(parse_error(number, format!("expected at least {} columns, found {}", columns.0.max(columns.1) + 1, fields.len()))),
+                Some(field) => delimiter.number(field).filter(|value| value.is_finite())
+                    .ok_or_else(|| parse_error(number, format!("expected a number, found \"{}\"", field))),
+            }
+        };
+        let point = (column(columns.0)?, column(columns.1)?);
+        if ring.is_empty() {
+            ring_start = number;
+        }
+        ring.push(point);
+    }
+    if !ring.is_empty() {
+        rings.push((ring_start, ring));
+    }
+
+    let mut segments = Vec::new();
+    for (start, mut ring) in rings {
+        // Rings written with their first point repeated at the end, or with repeated points, are fine
+        ring.dedup();
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        if ring.len() < 3 {
+            return Err(parse_error(start, format!("the ring starting here has {} distinct points, a loop needs at least 3", ring.len())));
+        }
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            segments.push([a.0, a.1, b.0, b.1]);
+        }
+    }
+    Ok(segments)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Delimiter {
+    Tab,
+    // Semicolons, with decimal commas
+    Semicolon,
+    Comma,
+    Whitespace,
+}
+
+impl Delimiter {
+    fn detect(line: &str) -> Delimiter {
+        if line.contains('\t') {
+            Delimiter::Tab
+        } else if line.contains(';') {
+            Delimiter::Semicolon
+        } else if line.contains(',') {
+            Delimiter::Comma
+        } else {
+            Delimiter::Whitespace
+        }
+    }
+
+    fn split(self, line: &str) -> Vec<&str> {
+        match self {
+            Delimiter::Tab => line.split('\t').map(str::trim).collect(),
+            Delimiter::Semicolon => line.split(';').map(str::trim).collect(),
+            Delimiter::Comma => line.split(',').map(str::trim).collect(),
+            Delimiter::Whitespace => line.split_whitespace().collect(),
+        }
+    }
+
+    fn number(self, field: &str) -> Option<f64> {
+        let field = field.trim_matches('"');
+        if self == Delimiter::Semicolon && !field.contains('.') {
+            field.replacen(',', ".", 1).parse().ok()
+        } else {
+            field.parse().ok()
+        }
+    }
+}
+
+// The x and y columns named in a header, if it names both
+fn header_columns(fields: &[&str]) -> Option<(usize, usize)> {
+    let find = |names: &[&str]| fields.iter().position(|field| {
+        let field = field.trim_matches('"');
+        names.iter().any(|name| field.eq_ignore_ascii_case(name))
+    });
+    Some((find(&["x", "easting", "e"])?, find(&["y", "northing", "n"])?))
+}
+
+fn parse_error(line: usize, message: String) -> ShapeError {
+    ShapeError::Parse { line: Some(line), message }
+}
@@ -0,0 +1,65 @@
+// Curves replaced by straight pieces, for the importers (svg.rs, dxf.rs), offsets (offset.rs) and fillets (corners.rs).
+// Each function returns the points along the curve after its start point, so consecutive curves and lines chain into one
+// polyline. tolerance is the furthest a piece may stray from the curve.
+use std::f64::consts::PI;
+
+type Point = (f64, f64);
+
+// Points along a quadratic or cubic Bézier curve. The number of pieces comes from the curve's second differences, which
+// bound how far a chord can stray from the curve (Wang's formula).
+pub(crate) fn bezier(controls: &[Point], tolerance: f64) -> Vec<Point> {
+    let degree = (controls.len() - 1) as f64;
+    let bend = controls.windows(3)
+        .map(|w| (w[0].0 - 2.0 * w[1].0 + w[2].0).hypot(w[0].1 - 2.0 * w[1].1 + w[2].1))
+        .fold(0.0, f64::max);
+    let pieces = pieces((degree * (degree - 1.0) / 8.0 * bend / tolerance).sqrt().ceil());
+    (1..=pieces).map(|i| {
+        let t = i as f64 / pieces as f64;
+        // de Casteljau
+        let mut points = controls.to_vec();
+        while points.len() > 1 {
+            points = points.windows(2).map(|w| (w[0].0 + t * (w[1].0 - w[0].0), w[0].1 + t * (w[1].1 - w[0].1))).collect();
+        }
+        points[0]
+    }).collect()
+}
+
+// Points along an ellipse with radii rx and ry, turned by rotation, from angle start through sweep (all in radians,
+// positive from the x axis towards the y axis). Each piece spans at most the angle whose chord stays within tolerance of
+// the larger radius, and never more than a quarter turn.
+pub(crate) fn ellipse_points(center: Point, rx: f64, ry: f64, rotation: f64, start: f64, sweep: f64, tolerance: f64) -> Vec<Point> {
+    let radius = rx.max(ry);
+    let step = if tolerance < radius { 2.0 * (1.0 - tolerance / radius).acos() } else { PI / 2.0 };
+    let pieces = pieces((sweep.abs() / step.min(PI / 2.0)).ceil());
+    let (sin, cos) = rotation.sin_cos();
+    (1..=pieces).map(|i| {
+        let theta = start + sweep * i as f64 / pieces as f64;
+        let (x, y) = (rx * theta.cos(), ry * theta.sin());
+        (center.0 + cos * x - sin * y, center.1 + sin * x + cos * y)
+    }).collect()
+}
+
+// Points along the circular arc from `from` to `to` that turns through sweep radians, ending exactly on `to`. A sweep of
+// zero is the straight line between them.
+pub(crate) fn arc_through(from: Point, to: Point, sweep: f64, tolerance: f64) -> Vec<Point> {
+    let chord = (to.0 - from.0).hypot(to.1 - from.1);
+    if sweep == 0.0 || chord == 0.0 {
+        return vec![to];
+    }
+    let radius = chord / (2.0 * (sweep / 2.0).sin().abs());
+    // The center sits on the chord's perpendicular bisector, to the left of the chord for sweeps between 0 and π
+    let offset = chord / 2.0 / (sweep / 2.0).tan();
+    let (ux, uy) = ((to.0 - from.0) / chord, (to.1 - from.1) / chord);
+    let center = ((from.0 + to.0) / 2.0 - uy * offset, (from.1 + to.1) / 2.0 + ux * offset);
+    let start = (from.1 - center.1).atan2(from.0 - center.0);
+    let mut points = ellipse_points(center, radius, radius, 0.0, start, sweep, tolerance);
+    if let Some(last) = points.last_mut() {
+        *last = to;
+    }
+    points
+}
+
+// Whole number of pieces to cut a curve into, at least 1 and at most a sensible limit
+fn pieces(count: f64) -> usize {
+    if count.is_nan() { 1 } else { count.clamp(1.0, 100_000.0) as usize }
+}
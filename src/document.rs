@@ -0,0 +1,190 @@
+// Versioned JSON documents for saving drawings (ShapeBuilder::to_json) and opening them again (ShapeBuilder::from_json).
+//
+// Version 2, the current format:
+//
+//     {
+//       "format": "centroid-calculator",
+//       "version": 2,
+//       "units": "mm",
+//       "metadata": {
+//         "project": "Bridge deck"
+//       },
+//       "parts": [
+//         {"name":"web","at":[0.5,2.0]}
+//       ],
+//       "segments": [
+//         [0.0,0.0,4.0,0.0],
+//         [4.0,0.0,4.0,1.0]
+//       ]
+//     }
+//
+// Each segment is [start x, start y, end x, end y], in edge ID order. Metadata keys are sorted and every segment and part
+// sits on its own line, so saved drawings diff cleanly in version control.
+//
+// Version 1 is the older `{"lines": [[[x1, y1], [x2, y2]], ...]}` produced by serializing ShapeBuilder with serde. It has no
+// "version" field. Older documents are migrated one version at a time until they reach the current one.
+use std::collections::BTreeMap;
+use std::io;
+use serde::{Deserialize, Serialize};
+use serde_json::ser::{Formatter, PrettyFormatter};
+use serde_json::{json, Value};
+use crate::error::ShapeError;
+
+pub(crate) const FORMAT: &str = "centroid-calculator";
+pub(crate) const CURRENT_VERSION: u64 = 2;
+
+// A named part of the drawing, labelled by a point inside it, like a room name written on a floor plan. The label stays
+// with the part however its lines are later split or merged.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Part {
+    pub name: String,
+    pub at: (f64, f64),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Document {
+    pub format: String,
+    pub version: u64,
+    #[serde(default)]
+    pub units: String,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    pub parts: Vec<Part>,
+    pub segments: Vec<[f64; 4]>,
+}
+
+impl Document {
+    pub(crate) fn to_json(&self) -> String {
+        let mut out = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(&mut out, ShallowPretty::default());
+        self.serialize(&mut serializer).expect("documents only hold strings and finite numbers");
+        String::from_utf8(out).expect("serde_json writes UTF-8")
+    }
+
+    // Reads a document of any known version
+    pub(crate) fn from_json(json: &str) -> Result<Document, ShapeError> {
+        let value: Value = serde_json::from_str(json).map_err(parse_error)?;
+        let value = migrate(value)?;
+        let document: Document = serde_json::from_value(value).map_err(parse_error)?;
+        if document.format != FORMAT {
+            return Err(invalid(format!("not a {} document (format is \"{}\")", FORMAT, document.format)));
+        }
+        Ok(document)
+    }
+}
+
+// Upgrades a document to the current version
+fn migrate(mut value: Value) -> Result<Value, ShapeError> {
+    if !value.is_object() {
+        return Err(invalid("expected a JSON object".to_string()));
+    }
+    let mut version = match value.get("version") {
+        None => 1,
+        Some(version) => version.as_u64().ok_or_else(|| invalid("\"version\" must be a whole number".to_string()))?,
+    };
+    if version > CURRENT_VERSION {
+        return Err(invalid(format!("document version {} is newer than this build supports ({})", version, CURRENT_VERSION)));
+    }
+    while version < CURRENT_VERSION {
+        value = match version {
+            1 => version_1_to_2(value)?,
+            _ => return Err(invalid(format!("unknown document version {}", version))),
+        };
+        version += 1;
+    }
+    Ok(value)
+}
+
+// {"lines": [[[x1, y1], [x2, y2]], ...]} -> version 2, with no units, metadata or parts
+fn version_1_to_2(value: Value) -> Result<Value, ShapeError> {
+    let lines = value.get("lines").and_then(Value::as_array)
+        .ok_or_else(|| invalid("version 1 documents need a \"lines\" array".to_string()))?;
+    let mut segments = Vec::new();
+    for line in lines {
+        let point = |i: usize| line.get(i).and_then(Value::as_array).filter(|point| point.len() == 2).cloned();
+        match (point(0), point(1)) {
+            (Some(start), Some(end)) => segments.push(json!([start[0], start[1], end[0], end[1]])),
+            _ => return Err(invalid(format!("expected a line as [[x1, y1], [x2, y2]], found {}", line))),
+        }
+    }
+    Ok(json!({ "format": FORMAT, "version": 2, "segments": segments }))
+}
+
+fn parse_error(error: serde_json::Error) -> ShapeError {
+    ShapeError::Parse { line: Some(error.line()).filter(|&line| line > 0), message: error.to_string() }
+}
+
+fn invalid(message: String) -> ShapeError {
+    ShapeError::Parse { line: None, message }
+}
+
+// Pretty printing for the top two levels only: the document's fields and the entries of its lists each get a line, while
+// anything nested deeper (a segment's coordinates, a part) stays on one line
+#[derive(Default)]
+struct ShallowPretty {
+    pretty: PrettyFormatter<'static>,
+    depth: usize,
+}
+
+impl ShallowPretty {
+    fn is_pretty(&self) -> bool {
+        self.depth <= 2
+    }
+}
+
+impl Formatter for ShallowPretty {
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        if self.is_pretty() { self.pretty.begin_array(writer) } else { writer.write_all(b"[") }
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let result = if self.is_pretty() { self.pretty.end_array(writer) } else { writer.write_all(b"]") };
+        self.depth -= 1;
+        result
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        if self.is_pretty() {
+            self.pretty.begin_array_value(writer, first)
+        } else if first {
+            Ok(())
+        } else {
+            writer.write_all(b",")
+        }
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.is_pretty() { self.pretty.end_array_value(writer) } else { Ok(()) }
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        if self.is_pretty() { self.pretty.begin_object(writer) } else { writer.write_all(b"{") }
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let result = if self.is_pretty() { self.pretty.end_object(writer) } else { writer.write_all(b"}") };
+        self.depth -= 1;
+        result
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        if self.is_pretty() {
+            self.pretty.begin_object_key(writer, first)
+        } else if first {
+            Ok(())
+        } else {
+            writer.write_all(b",")
+        }
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.is_pretty() { self.pretty.begin_object_value(writer) } else { writer.write_all(b":") }
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.is_pretty() { self.pretty.end_object_value(writer) } else { Ok(()) }
+    }
+}
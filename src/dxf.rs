@@ -0,0 +1,364 @@
+// DXF import and export: reads the LINE, LWPOLYLINE, ARC and CIRCLE entities of an ASCII DXF file for
+// ShapeBuilder::import_dxf, and writes the drawing with its computed properties as DXF R12 for ShapeBuilder::to_dxf.
+//
+// Import:
+//
+// A DXF file is a list of pairs of lines, a group code and a value. Only two sections matter here: HEADER, for the
+// drawing units ($INSUNITS), and ENTITIES, for the geometry. Blocks and the INSERTs placing them aren't expanded, and
+// other entity types are skipped. Polyline bulges, arcs and circles are replaced by straight segments within tolerance.
+// Entities drawn with their extrusion direction pointing down (mirrored in CAD) are mirrored back into the xy plane, and
+// arc ends are snapped onto the vertices they were meant to meet.
+//
+// Export writes R12 (AC1009), which every CAD program reads. The segments go on the GEOMETRY layer as LINEs, the
+// centroid on CENTROID as a POINT, the principal axes on AXES as LINEs, and the section properties on ANNOTATIONS as TEXT
+// beside the drawing. Coordinates are written unchanged, so importing the GEOMETRY layer gives back the same segments.
+// $INSUNITS is newer than R12, but is written anyway when the units are known: R12 readers skip header variables they
+// don't know.
+use std::collections::HashMap;
+use std::fmt::Write;
+use crate::analysis::Analysis;
+use crate::curves::{arc_through, ellipse_points};
+use crate::error::ShapeError;
+use crate::format;
+
+type Point = (f64, f64);
+
+// Unit names for the $INSUNITS codes, and their length in metres
+const INSUNITS: [(i64, &str, f64); 14] = [
+    (1, "in", 0.0254),
+    (2, "ft", 0.3048),
+    (3, "mi", 1609.344),
+    (4, "mm", 0.001),
+    (5, "cm", 0.01),
+    (6, "m", 1.0),
+    (7, "km", 1000.0),
+    (8, "µin", 2.54e-8),
+    (9, "mil", 2.54e-5),
+    (10, "yd", 0.9144),
+    (11, "Å", 1e-10),
+    (12, "nm", 1e-9),
+    (13, "µm", 1e-6),
+    (14, "dm", 0.1),
+];
+
+// What a DXF file holds: its segments as [x1, y1, x2, y2], and the unit name from $INSUNITS if it has one, with the
+// (1-based) line its code is on
+pub(crate) struct Drawing {
+    pub segments: Vec<[f64; 4]>,
+    pub units: Option<&'static str>,
+    pub units_line: usize,
+}
+
+// One group code and value, with the index of the line the code is on (counting from 0)
+struct Pair<'a> {
+    code: i32,
+    value: &'a str,
+    line: usize,
+}
+
+impl Pair<'_> {
+    fn number(&self) -> Result<f64, ShapeError> {
+        self.value.parse::<f64>().map_err(|_| parse_error(self.line + 2, format!("expected a number for group code {}, found \"{}\"", self.code, self.value)))
+    }
+}
+
+// Reads the file, keeping only entities on the given layers (all of them when layers is empty; names are compared
+// ignoring case, like CAD programs do)
+pub(crate) fn parse(dxf: &str, layers: &[String], tolerance: f64) -> Result<Drawing, ShapeError> {
+    if dxf.starts_with("AutoCAD Binary DXF") {
+        return Err(parse_error(1, "binary DXF files aren't supported, save it as ASCII DXF".to_string()));
+    }
+    let pairs = pairs(dxf)?;
+    let mut drawing = Drawing { segments: Vec::new(), units: None, units_line: 0 };
+    // Polylines drawn by the entities, and whether their ends were worked out from angles rather than given
+    let mut polylines: Vec<(Vec<Point>, bool)> = Vec::new();
+    let mut section = "";
+    let mut i = 0;
+    while i < pairs.len() {
+        let pair = &pairs[i];
+        match (pair.code, pair.value) {
+            (0, "SECTION") => {
+                section = pairs.get(i + 1).filter(|name| name.code == 2).map_or("", |name| name.value);
+                i += 1;
+            }
+            (0, "ENDSEC") => section = "",
+            (9, "$INSUNITS") if section == "HEADER" => {
+                if let Some(value) = pairs.get(i + 1).filter(|value| value.code == 70) {
+                    let code = value.value.parse::<i64>().map_err(|_| parse_error(value.line + 2, format!("invalid $INSUNITS \"{}\"", value.value)))?;
+                    drawing.units = INSUNITS.iter().find(|unit| unit.0 == code).map(|unit| unit.1);
+                    drawing.units_line = value.line + 2;
+                }
+            }
+            (0, kind) if section == "ENTITIES" => {
+                // The entity's own pairs run up to the next code 0
+                let end = pairs[i + 1..].iter().position(|pair| pair.code == 0).map_or(pairs.len(), |n| i + 1 + n);
+                let fields = &pairs[i + 1..end];
+                let layer = fields.iter().find(|field| field.code == 8).map_or("0", |field| field.value);
+                if layers.is_empty() || layers.iter().any(|wanted| wanted.eq_ignore_ascii_case(layer)) {
+                    polylines.push(entity(kind, fields, pair.line, tolerance)?);
+                }
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    snap_arc_ends(&mut polylines);
+    for (points, _) in polylines {
+        for pair in points.windows(2) {
+            if pair[0] != pair[1] {
+                drawing.segments.push([pair[0].0, pair[0].1, pair[1].0, pair[1].1]);
+            }
+        }
+    }
+    Ok(drawing)
+}
+
+// An arc's ends are worked out from its center, radius and angles, so they miss the line or arc they're meant to join by
+// a rounding error. Moves them onto any given vertex, or earlier arc end, within a billionth of the drawing's size.
+fn snap_arc_ends(polylines: &mut [(Vec<Point>, bool)]) {
+    let (mut min, mut max) = ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY));
+    for &(x, y) in polylines.iter().flat_map(|(points, _)| points) {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    let reach = 1e-9 * (max.0 - min.0).max(max.1 - min.1);
+    if reach.is_nan() || reach <= 0.0 {
+        return;
+    }
+    // Points to snap to, in cells as large as the snapping distance
+    let mut cells: HashMap<(i64, i64), Vec<Point>> = HashMap::new();
+    let cell = |point: Point| ((point.0 / reach).floor() as i64, (point.1 / reach).floor() as i64);
+    for (points, _) in polylines.iter().filter(|(_, computed)| !computed) {
+        for &point in points {
+            cells.entry(cell(point)).or_default().push(point);
+        }
+    }
+    for (points, _) in polylines.iter_mut().filter(|(_, computed)| *computed) {
+        let last = points.len() - 1;
+        for index in [0, last] {
+            let point = points[index];
+            let (column, row) = cell(point);
+            let nearest = (column - 1..=column + 1)
+                .flat_map(|x| (row - 1..=row + 1).map(move |y| (x, y)))
+                .filter_map(|key| cells.get(&key))
+                .flatten()
+                .copied()
+                .filter(|other| (other.0 - point.0).hypot(other.1 - point.1) <= reach)
+                .min_by(|a, b| (a.0 - point.0).hypot(a.1 - point.1).total_cmp(&(b.0 - point.0).hypot(b.1 - point.1)));
+            match nearest {
+                Some(nearest) => points[index] = nearest,
+                None => cells.entry((column, row)).or_default().push(point),
+            }
+        }
+    }
+}
+
+// How many of `to` make one `from`, for two unit names from $INSUNITS. None if either isn't one of them.
+pub(crate) fn unit_scale(from: &str, to: &str) -> Option<f64> {
+    let metres = |name: &str| INSUNITS.iter().find(|unit| unit.1 == name).map(|unit| unit.2);
+    Some(metres(from)? / metres(to)?)
+}
+
+// Splits the file into its group code and value pairs
+fn pairs(dxf: &str) -> Result<Vec<Pair<'_>>, ShapeError> {
+    let lines: Vec<&str> = dxf.lines().collect();
+    let mut pairs = Vec::with_capacity(lines.len() / 2);
+    for (n, chunk) in lines.chunks(2).enumerate() {
+        let line = 2 * n;
+        let code = chunk[0].trim();
+        if code.is_empty() && chunk.len() == 1 {
+            break; // Trailing blank line
+        }
+        let code = code.parse::<i32>().map_err(|_| parse_error(line + 1, format!("expected a group code, found \"{}\"", code)))?;
+        let value = chunk.get(1).ok_or_else(|| parse_error(line + 1, format!("group code {} has no value", code)))?;
+        pairs.push(Pair { code, value: value.trim(), line });
+    }
+    Ok(pairs)
+}
+
+// The polyline an entity draws, and whether its ends were worked out from angles (for arcs)
+fn entity(kind: &str, fields: &[Pair], line: usize, tolerance: f64) -> Result<(Vec<Point>, bool), ShapeError> {
+    let value = |code: i32| -> Result<Option<f64>, ShapeError> {
+        fields.iter().find(|field| field.code == code).map(Pair::number).transpose()
+    };
+    let required = |code: i32| -> Result<f64, ShapeError> {
+        value(code)?.ok_or_else(|| parse_error(line + 1, format!("{} is missing group code {}", kind, code)))
+    };
+    // Except for lines, coordinates are in the entity's own plane, which for entities extruded along -z is mirrored in x
+    let mirror = if kind != "LINE" && value(230)?.unwrap_or(1.0) < 0.0 { -1.0 } else { 1.0 };
+
+    let points = match kind {
+        "LINE" => vec![(required(10)?, required(20)?), (required(11)?, required(21)?)],
+        "LWPOLYLINE" => {
+            // Each vertex is a 10/20 pair, optionally followed by the bulge (42) of the segment leaving it
+            let mut vertices: Vec<(Point, f64)> = Vec::new();
+            for field in fields {
+                match field.code {
+                    10 => vertices.push(((field.number()?, 0.0), 0.0)),
+                    20 | 42 => {
+                        let last = vertices.last_mut().ok_or_else(|| parse_error(field.line + 1, format!("group code {} before the first vertex", field.code)))?;
+                        if field.code == 20 { last.0.1 = field.number()? } else { last.1 = field.number()? }
+                    }
+                    _ => {}
+                }
+            }
+            let closed = value(70)?.is_some_and(|flags| flags as i64 & 1 == 1);
+            let count = if closed { vertices.len() } else { vertices.len().saturating_sub(1) };
+            let mut points: Vec<Point> = vertices.first().map(|vertex| vertex.0).into_iter().collect();
+            for i in 0..count {
+                let ((from, bulge), (to, _)) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+                // The bulge is the tangent of a quarter of the arc's angle, positive counter-clockwise
+                points.extend(arc_through(from, to, 4.0 * bulge.atan(), tolerance));
+            }
+            points
+        }
+        "ARC" | "CIRCLE" => {
+            let (center, radius) = ((required(10)?, required(20)?), required(40)?);
+            if radius <= 0.0 {
+                return Ok((Vec::new(), false));
+            }
+            // Arcs run counter-clockwise from the start angle to the end angle
+            let (start, sweep) = if kind == "ARC" {
+                let (start, end) = (required(50)?.to_radians(), required(51)?.to_radians());
+                let sweep = (end - start).rem_euclid(2.0 * std::f64::consts::PI);
+                (start, if sweep == 0.0 { 2.0 * std::f64::consts::PI } else { sweep })
+            } else {
+                (0.0, 2.0 * std::f64::consts::PI)
+            };
+            let mut points = vec![(center.0 + radius * start.cos(), center.1 + radius * start.sin())];
+            points.extend(ellipse_points(center, radius, radius, 0.0, start, sweep, tolerance));
+            if kind == "CIRCLE" {
+                *points.last_mut().unwrap() = points[0];
+            }
+            points
+        }
+        _ => return Ok((Vec::new(), false)),
+    };
+    Ok((points.into_iter().map(|(x, y)| (mirror * x, y)).collect(), kind == "ARC"))
+}
+
+fn parse_error(line: usize, message: String) -> ShapeError {
+    ShapeError::Parse { line: Some(line), message }
+}
+
+// Layers of an exported file, with their colour numbers
+const LAYERS: [(&str, i32); 4] = [("GEOMETRY", 7), ("CENTROID", 3), ("AXES", 1), ("ANNOTATIONS", 5)];
+
+// The drawing as a DXF R12 file. analysis is left out when the lines don't enclose anything, which leaves only the
+// GEOMETRY layer with anything on it.
+pub(crate) fn write(lines: &[Vec<Point>], analysis: Option<&Analysis>, units: &str) -> String {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in lines.iter().flatten() {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let size = if lines.is_empty() { 1.0 } else { (max_x - min_x).max(max_y - min_y) };
+    let mut out = Out::default();
+
+    out.pairs(&[(0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1009")]);
+    if let Some(unit) = INSUNITS.iter().find(|unit| unit.1 == units) {
+        out.pair(9, "$INSUNITS");
+        out.pair(70, unit.0);
+    }
+    // Show points as a circle with a cross in it, a fiftieth of the drawing across
+    out.pairs(&[(9, "$PDMODE"), (70, "34"), (9, "$PDSIZE")]);
+    out.pair(40, size / 50.0);
+    out.pair(0, "ENDSEC");
+
+    out.pairs(&[(0, "SECTION"), (2, "TABLES")]);
+    out.pairs(&[(0, "TABLE"), (2, "LTYPE"), (70, "1")]);
+    out.pairs(&[(0, "LTYPE"), (2, "CONTINUOUS"), (70, "0"), (3, "Solid line"), (72, "65"), (73, "0"), (40, "0.0")]);
+    out.pair(0, "ENDTAB");
+    out.pairs(&[(0, "TABLE"), (2, "LAYER")]);
+    out.pair(70, LAYERS.len());
+    for (name, colour) in LAYERS {
+        out.pairs(&[(0, "LAYER"), (2, name), (70, "0")]);
+        out.pair(62, colour);
+        out.pair(6, "CONTINUOUS");
+    }
+    out.pairs(&[(0, "ENDTAB"), (0, "ENDSEC")]);
+
+    out.pairs(&[(0, "SECTION"), (2, "ENTITIES")]);
+    for segment in lines {
+        out.line("GEOMETRY", segment[0], segment[1]);
+    }
+    if let Some(analysis) = analysis {
+        let section = &analysis.section;
+        let (cx, cy) = section.centroid;
+        out.pairs(&[(0, "POINT"), (8, "CENTROID")]);
+        out.point(10, (cx, cy));
+
+        // Principal axes through the centroid, as long as the drawing's diagonal
+        let reach = (max_x - min_x).hypot(max_y - min_y) / 2.0;
+        for angle in [section.principal_angle, section.principal_angle + std::f64::consts::FRAC_PI_2] {
+            let (dx, dy) = (reach * angle.cos(), reach * angle.sin());
+            out.line("AXES", (cx - dx, cy - dy), (cx + dx, cy + dy));
+        }
+
+        // The properties listed to the right of the drawing, top to bottom
+        let unit = |power: &str| if units.is_empty() { String::new() } else { format!(" {}{}", units, power) };
+        let (rx, ry) = section.radii_of_gyration();
+        let text = [
+            format!("Area = {}{}", label(section.area), unit("^2")),
+            format!("Centroid = ({}, {}){}", label(cx), label(cy), unit("")),
+            format!("Ixx = {}{}", label(section.ixx), unit("^4")),
+            format!("Iyy = {}{}", label(section.iyy), unit("^4")),
+            format!("Ixy = {}{}", label(section.ixy), unit("^4")),
+            format!("I major = {}{}", label(section.i_major), unit("^4")),
+            format!("I minor = {}{}", label(section.i_minor), unit("^4")),
+            format!("Principal angle = {} deg", label(section.principal_angle.to_degrees())),
+            format!("Radii of gyration = ({}, {}){}", label(rx), label(ry), unit("")),
+        ];
+        let height = size / 30.0;
+        for (i, text) in text.iter().enumerate() {
+            out.pairs(&[(0, "TEXT"), (8, "ANNOTATIONS")]);
+            out.point(10, (max_x + size / 10.0, max_y - height - 1.5 * height * i as f64));
+            out.pair(40, height);
+            out.pair(1, text);
+        }
+    }
+    out.pairs(&[(0, "ENDSEC"), (0, "EOF")]);
+    out.text
+}
+
+// DXF text being written
+#[derive(Default)]
+struct Out {
+    text: String,
+}
+
+impl Out {
+    fn pair(&mut self, code: i32, value: impl std::fmt::Display) {
+        // Writing to a String can't fail
+        let _ = write!(self.text, "{:>3}\n{}\n", code, value);
+    }
+
+    fn pairs(&mut self, pairs: &[(i32, &str)]) {
+        for &(code, value) in pairs {
+            self.pair(code, value);
+        }
+    }
+
+    // A point in the xy plane, as x, y and z under the group codes code, code + 10 and code + 20
+    fn point(&mut self, code: i32, (x, y): Point) {
+        self.pair(code, x);
+        self.pair(code + 10, y);
+        self.pair(code + 20, 0.0);
+    }
+
+    fn line(&mut self, layer: &str, a: Point, b: Point) {
+        self.pairs(&[(0, "LINE"), (8, layer)]);
+        self.point(10, a);
+        self.point(11, b);
+    }
+}
+
+// A number for an annotation, to four decimal places
+fn label(value: f64) -> String {
+    format::decimals(value, 4)
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+// Everything that can go wrong while building a shape or computing its properties.
+// Native callers get these back in a Result, Javascript callers get them thrown as exceptions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    // The lines don't enclose any region
+    NotClosed,
+    // Not enough geometry to work with, e.g. a line of zero length or fewer than three lines
+    Degenerate,
+    // A coordinate is NaN or infinite
+    NaNInput,
+    // The enclosed regions cancel each other out and leave no area
+    ZeroArea,
+    // Two stored lines cross without being split where they meet
+    SelfIntersecting,
+    // A vertex or edge ID that doesn't exist (any more)
+    InvalidId,
+    // The result of an edit would have a hole touching its outline. Every face enclosed by connected lines counts as
+    // solid, so the drawing can't show such a hole.
+    TouchingHole,
+    // A line can't be extended to meet the chosen edge: it's parallel to it, would pass beside it, or already reaches it
+    NoIntersection,
+    // A file or document that can't be read. line is 1-based, or None when the problem isn't on a particular line.
+    Parse { line: Option<usize>, message: String },
+}
+
+impl ShapeError {
+    // Short, stable name of the error, exposed to Javascript as `error.kind`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ShapeError::NotClosed => "NotClosed",
+            ShapeError::Degenerate => "Degenerate",
+            ShapeError::NaNInput => "NaNInput",
+            ShapeError::ZeroArea => "ZeroArea",
+            ShapeError::SelfIntersecting => "SelfIntersecting",
+            ShapeError::InvalidId => "InvalidId",
+            ShapeError::TouchingHole => "TouchingHole",
+            ShapeError::NoIntersection => "NoIntersection",
+            ShapeError::Parse { .. } => "Parse",
+        }
+    }
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::NotClosed => write!(f, "the lines do not enclose a region"),
+            ShapeError::Degenerate => write!(f, "not enough geometry to work with"),
+            ShapeError::NaNInput => write!(f, "coordinates must be finite numbers"),
+            ShapeError::ZeroArea => write!(f, "the enclosed regions have no area"),
+            ShapeError::SelfIntersecting => write!(f, "two lines cross without sharing a vertex"),
+            ShapeError::InvalidId => write!(f, "no vertex or edge has this ID"),
+            ShapeError::TouchingHole => write!(f, "the result has a hole touching its outline, which a drawing can't show"),
+            ShapeError::NoIntersection => write!(f, "the line doesn't meet that edge when extended"),
+            ShapeError::Parse { line: None, message } => write!(f, "could not read the document: {}", message),
+            ShapeError::Parse { line: Some(line), message } => write!(f, "could not read line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+// Thrown to Javascript as an Error with name "ShapeError", the readable description as its message and the variant name
+// in its `kind` field. A Parse error also has its `line`.
+impl From<ShapeError> for JsValue {
+    fn from(error: ShapeError) -> JsValue {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("ShapeError");
+        let set = |field: &str, value: JsValue| {
+            let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str(field), &value);
+        };
+        set("kind", JsValue::from_str(error.kind()));
+        if let ShapeError::Parse { line, .. } = &error {
+            set("line", line.map_or(JsValue::NULL, |line| JsValue::from_f64(line as f64)));
+        }
+        js_error.into()
+    }
+}
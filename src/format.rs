@@ -0,0 +1,9 @@
+// Number formatting shared by the SVG and DXF writers and the centroid-calc table, for the labels and annotations people
+// read rather than the coordinates programs read back (those are written in full).
+
+// A number with at most the given number of decimal places, without trailing zeros or a negative zero
+pub fn decimals(value: f64, places: usize) -> String {
+    let text = format!("{:.*}", places, value);
+    let text = if text.contains('.') { text.trim_end_matches('0').trim_end_matches('.') } else { &text };
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
@@ -0,0 +1,346 @@
+// Well-Known Text and GeoJSON, for ShapeBuilder::import_wkt, import_geojson, to_wkt and to_geojson, so drawings can be
+// exchanged with spatial databases and mapping tools (e.g. to find the centroid of a land parcel).
+//
+// Reading takes the rings of POLYGON and MULTIPOLYGON geometries (Polygon and MultiPolygon in GeoJSON, also inside
+// Features, FeatureCollections and GeometryCollections) as segments. Holes need no special handling: a ring inside another
+// cuts a hole in it as usual. Z and M values are dropped.
+//
+// Writing describes the solid region rather than the faces of the drawing: faces sharing an edge are merged, and the
+// region's outline is traced into exterior rings (counter-clockwise) and holes (clockwise), as GeoJSON's right-hand rule
+// asks. Coordinates are written unchanged, so they should already be in a projected (planar) coordinate system for the
+// area and centroid to be meaningful.
+use std::collections::HashMap;
+use serde_json::{json, Value};
+use crate::analysis::Analysis;
+use crate::error::ShapeError;
+use crate::loops::{self, Loop};
+
+type Point = (f64, f64);
+
+// One polygon: its exterior ring followed by its holes, each without repeating the first point at the end
+type Polygon = Vec<Vec<Point>>;
+
+// An edge looked up by the keys of its end points, smaller first
+type EdgeKey = ((u64, u64), (u64, u64));
+
+// Segments, as [x1, y1, x2, y2], of every ring of the polygons in a WKT string
+pub(crate) fn parse_wkt(wkt: &str) -> Result<Vec<[f64; 4]>, ShapeError> {
+    let mut reader = Wkt { text: wkt, position: 0 };
+    let mut segments = Vec::new();
+    // Extended WKT from PostGIS starts with the coordinate system, e.g. "SRID=4326;"
+    if reader.rest().get(..5).is_some_and(|start| start.eq_ignore_ascii_case("SRID=")) {
+        match reader.rest().find(';') {
+            Some(end) => reader.position += end + 1,
+            None => return Err(reader.error("expected \";\" after the SRID")),
+        }
+    }
+    let kind = reader.word()?.to_ascii_uppercase();
+    // Dimension markers only say how many numbers each point has
+    let mut dimensions = 2;
+    let next = reader.peek_word().to_ascii_uppercase();
+    if matches!(next.as_str(), "Z" | "M" | "ZM") {
+        dimensions += next.len();
+        reader.word()?;
+    }
+    match kind.as_str() {
+        "POLYGON" => {
+            if !reader.empty()? {
+                reader.polygon(dimensions, &mut segments)?;
+            }
+        }
+        "MULTIPOLYGON" => {
+            if !reader.empty()? {
+                reader.expect('(')?;
+                loop {
+                    if !reader.empty()? {
+                        reader.polygon(dimensions, &mut segments)?;
+                    }
+                    if !reader.list_continues()? {
+                        break;
+                    }
+                }
+            }
+        }
+        _ => return Err(reader.error(&format!("unsupported geometry type \"{}\", expected POLYGON or MULTIPOLYGON", kind))),
+    }
+    if !reader.rest().trim().is_empty() {
+        return Err(reader.error("unexpected text after the geometry"));
+    }
+    Ok(segments)
+}
+
+// Reads WKT from left to right
+struct Wkt<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Wkt<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.position = self.text.len() - self.rest().trim_start().len();
+    }
+
+    fn error(&self, message: &str) -> ShapeError {
+        let line = self.text[..self.position].matches('\n').count() + 1;
+        ShapeError::Parse { line: Some(line), message: message.to_string() }
+    }
+
+    fn peek_word(&mut self) -> &str {
+        self.skip_whitespace();
+        let length = self.rest().find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(self.rest().len());
+        &self.rest()[..length]
+    }
+
+    fn word(&mut self) -> Result<String, ShapeError> {
+        let word = self.peek_word().to_string();
+        if word.is_empty() {
+            return Err(self.error("expected a geometry type"));
+        }
+        self.position += word.len();
+        Ok(word)
+    }
+
+    // Reads EMPTY if it comes next
+    fn empty(&mut self) -> Result<bool, ShapeError> {
+        if self.peek_word().eq_ignore_ascii_case("EMPTY") {
+            self.word()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn expect(&mut self, wanted: char) -> Result<(), ShapeError> {
+        self.skip_whitespace();
+        if self.rest().starts_with(wanted) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected \"{}\"", wanted)))
+        }
+    }
+
+    // After an item in a list: true for a comma (another item follows), false for the closing parenthesis
+    fn list_continues(&mut self) -> Result<bool, ShapeError> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some(',') => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(')') => {
+                self.position += 1;
+                Ok(false)
+            }
+            _ => Err(self.error("expected \",\" or \")\"")),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, ShapeError> {
+        self.skip_whitespace();
+        let length = self.rest().find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))).unwrap_or(self.rest().len());
+        match self.rest()[..length].parse::<f64>() {
+            Ok(value) => {
+                self.position += length;
+                Ok(value)
+            }
+            Err(_) => Err(self.error("expected a number")),
+        }
+    }
+
+    // ((x y, ...), (x y, ...), ...)
+    fn polygon(&mut self, dimensions: usize, segments: &mut Vec<[f64; 4]>) -> Result<(), ShapeError> {
+        self.expect('(')?;
+        loop {
+            self.expect('(')?;
+            let mut ring = Vec::new();
+            loop {
+                let (x, y) = (self.number()?, self.number()?);
+                for _ in 2..dimensions {
+                    self.number()?;
+                }
+                ring.push((x, y));
+                if !self.list_continues()? {
+                    break;
+                }
+            }
+            ring_segments(&ring, segments);
+            if !self.list_continues()? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Segments, as [x1, y1, x2, y2], of every ring of the polygons in a GeoJSON geometry, Feature or FeatureCollection. Other
+// geometry types, such as points labelling the parcels, are skipped.
+pub(crate) fn parse_geojson(geojson: &str) -> Result<Vec<[f64; 4]>, ShapeError> {
+    let value: Value = serde_json::from_str(geojson)
+        .map_err(|error| ShapeError::Parse { line: Some(error.line()).filter(|&line| line > 0), message: error.to_string() })?;
+    let mut segments = Vec::new();
+    geojson_object(&value, &mut segments)?;
+    Ok(segments)
+}
+
+fn geojson_object(value: &Value, segments: &mut Vec<[f64; 4]>) -> Result<(), ShapeError> {
+    let list = |key: &str| value.get(key).and_then(Value::as_array).ok_or_else(|| invalid(format!("expected a \"{}\" array", key)));
+    match value.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => list("features")?.iter().try_for_each(|feature| geojson_object(feature, segments)),
+        Some("Feature") => match value.get("geometry") {
+            Some(Value::Null) | None => Ok(()),
+            Some(geometry) => geojson_object(geometry, segments),
+        },
+        Some("GeometryCollection") => list("geometries")?.iter().try_for_each(|geometry| geojson_object(geometry, segments)),
+        Some("Polygon") => geojson_polygon(&value["coordinates"], segments),
+        Some("MultiPolygon") => list("coordinates")?.iter().try_for_each(|polygon| geojson_polygon(polygon, segments)),
+        Some(_) => Ok(()),
+        None => Err(invalid("expected a GeoJSON object with a \"type\"".to_string())),
+    }
+}
+
+// [[[x, y], ...], ...]
+fn geojson_polygon(rings: &Value, segments: &mut Vec<[f64; 4]>) -> Result<(), ShapeError> {
+    let rings = rings.as_array().ok_or_else(|| invalid(format!("expected a polygon as a list of rings, found {}", rings)))?;
+    for ring in rings {
+        let positions = ring.as_array().ok_or_else(|| invalid(format!("expected a ring as a list of positions, found {}", ring)))?;
+        let mut points = Vec::with_capacity(positions.len());
+        for position in positions {
+            let coordinate = |i: usize| position.get(i).and_then(Value::as_f64);
+            match (coordinate(0), coordinate(1)) {
+                (Some(x), Some(y)) => points.push((x, y)),
+                _ => return Err(invalid(format!("expected a position as [x, y], found {}", position))),
+            }
+        }
+        ring_segments(&points, segments);
+    }
+    Ok(())
+}
+
+// A ring's sides, closing it if its last point isn't its first and skipping repeated points
+fn ring_segments(ring: &[Point], segments: &mut Vec<[f64; 4]>) {
+    for (i, &a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        if a != b {
+            segments.push([a.0, a.1, b.0, b.1]);
+        }
+    }
+}
+
+fn invalid(message: String) -> ShapeError {
+    ShapeError::Parse { line: None, message }
+}
+
+// The solid region as WKT: a POLYGON, a MULTIPOLYGON when it has separate pieces, or POLYGON EMPTY
+pub(crate) fn write_wkt(analysis: Option<&Analysis>) -> String {
+    let polygons = analysis.map(|analysis| outline(&analysis.loops)).unwrap_or_default();
+    let polygon_text = |polygon: &Polygon| {
+        let rings: Vec<String> = polygon.iter().map(|ring| {
+            let points: Vec<String> = ring.iter().chain(ring.first()).map(|(x, y)| format!("{} {}", x, y)).collect();
+            format!("({})", points.join(", "))
+        }).collect();
+        format!("({})", rings.join(", "))
+    };
+    match polygons.as_slice() {
+        [] => "POLYGON EMPTY".to_string(),
+        [polygon] => format!("POLYGON {}", polygon_text(polygon)),
+        _ => format!("MULTIPOLYGON ({})", polygons.iter().map(polygon_text).collect::<Vec<_>>().join(", ")),
+    }
+}
+
+// The solid region as a GeoJSON Feature with its area and centroid as properties (and units, when set). Lines that don't
+// enclose anything give a Feature without geometry.
+pub(crate) fn write_geojson(analysis: Option<&Analysis>, units: &str) -> String {
+    let Some(analysis) = analysis else {
+        return json!({ "type": "Feature", "properties": {}, "geometry": null }).to_string();
+    };
+    let polygons = outline(&analysis.loops);
+    let coordinates = |polygon: &Polygon| -> Value {
+        polygon.iter().map(|ring| ring.iter().chain(ring.first()).map(|&(x, y)| json!([x, y])).collect::<Value>()).collect()
+    };
+    let geometry = match polygons.as_slice() {
+        [polygon] => json!({ "type": "Polygon", "coordinates": coordinates(polygon) }),
+        _ => json!({ "type": "MultiPolygon", "coordinates": polygons.iter().map(coordinates).collect::<Vec<_>>() }),
+    };
+    let (cx, cy) = analysis.section.centroid;
+    let mut properties = json!({ "area": analysis.section.area, "centroid": [cx, cy] });
+    if !units.is_empty() {
+        properties["units"] = json!(units);
+    }
+    json!({ "type": "Feature", "properties": properties, "geometry": geometry }).to_string()
+}
+
+// Outline of the solid region, as polygons with their holes. The region is what the loops cover an odd number of times,
+// so its boundary is made of the edges that appear in an odd number of loops: edges shared by two faces of the same kind
+// drop out.
+pub(crate) fn outline(found: &[Loop]) -> Vec<Polygon> {
+    // Each boundary edge, directed with the solid region on its left: loops are walked with their face on the left,
+    // which is solid for solid loops and a hole (with material on the right) for holes
+    let mut edges: HashMap<EdgeKey, (Point, Point, usize)> = HashMap::new();
+    for face in found {
+        for (i, &a) in face.points.iter().enumerate() {
+            let b = face.points[(i + 1) % face.points.len()];
+            let (ka, kb) = (loops::point_key(a), loops::point_key(b));
+            let key = if ka <= kb { (ka, kb) } else { (kb, ka) };
+            let directed = if face.is_hole() { (b, a) } else { (a, b) };
+            edges.entry(key).and_modify(|entry| entry.2 += 1).or_insert((directed.0, directed.1, 1));
+        }
+    }
+    let mut boundary: Vec<(Point, Point)> = edges.into_values().filter(|edge| edge.2 % 2 == 1).map(|edge| (edge.0, edge.1)).collect();
+    // Sorted so the output doesn't depend on hashing order
+    boundary.sort_by(|a, b| (a.0.0, a.0.1, a.1.0, a.1.1).partial_cmp(&(b.0.0, b.0.1, b.1.0, b.1.1)).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut outgoing: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, edge) in boundary.iter().enumerate() {
+        outgoing.entry(loops::point_key(edge.0)).or_default().push(i);
+    }
+
+    // Trace the rings. Where the outline touches itself at a vertex, leave along the edge just clockwise of the way back,
+    // as find_loops does, so each ring goes round one piece and they stay simple.
+    let mut used = vec![false; boundary.len()];
+    let mut rings: Vec<Vec<Point>> = Vec::new();
+    for start in 0..boundary.len() {
+        if used[start] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut current = start;
+        while !used[current] {
+            used[current] = true;
+            let (from, to) = boundary[current];
+            ring.push(from);
+            let back = (from.1 - to.1).atan2(from.0 - to.0);
+            let next = outgoing.get(&loops::point_key(to)).into_iter().flatten().copied()
+                .filter(|&candidate| !used[candidate] || candidate == start)
+                .min_by(|&a, &b| {
+                    let clockwise = |edge: usize| {
+                        let (p, q) = boundary[edge];
+                        (back - (q.1 - p.1).atan2(q.0 - p.0)).rem_euclid(2.0 * std::f64::consts::PI)
+                    };
+                    clockwise(a).total_cmp(&clockwise(b))
+                });
+            match next {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        if ring.len() >= 3 {
+            rings.push(ring);
+        }
+    }
+
+    // Counter-clockwise rings are exteriors and clockwise ones holes, each belonging to the smallest exterior around it
+    let (exteriors, holes): (Vec<Vec<Point>>, Vec<Vec<Point>>) = rings.into_iter().partition(|ring| loops::signed_area(ring) > 0.0);
+    let mut polygons: Vec<Polygon> = exteriors.into_iter().map(|ring| vec![ring]).collect();
+    for hole in holes {
+        let owner = (0..polygons.len())
+            .filter(|&i| hole.iter().any(|&point| loops::point_in_polygon(point, &polygons[i][0]) && !polygons[i][0].contains(&point)))
+            .min_by(|&a, &b| loops::signed_area(&polygons[a][0]).total_cmp(&loops::signed_area(&polygons[b][0])));
+        if let Some(owner) = owner {
+            polygons[owner].push(hole);
+        }
+    }
+    polygons
+}
@@ -0,0 +1,199 @@
+// Uniform grid over the stored lines, so finding the lines near a point or another line doesn't mean checking all of them.
+//
+// Each line is listed in every grid cell it passes through. ShapeBuilder keeps the grid in step with its lines: every
+// change to the lines, including undo and redo, goes through ShapeBuilder::place_line, ShapeBuilder::place_lines or
+// ShapeBuilder::take_line, which update the grid too. Lines are identified by their index in ShapeBuilder::lines. Those only ever swap a line with the
+// last one instead of shifting every line after it, so the grid only has to relabel that one line's entries.
+use std::collections::HashMap;
+
+type Point = (f64, f64);
+
+// Cell coordinates are widened by this fraction of a cell, so a line lying on a cell border, or a point computed with a
+// little rounding error, is still found from either side of it
+const PADDING: f64 = 1e-6;
+
+// Lines may be listed in this many cells per line on average (plus a fixed allowance) before the cells are made larger
+const CELLS_PER_LINE: usize = 8;
+const SPARE_CELLS: usize = 4096;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Grid {
+    // Width and height of a cell. Zero until the first line is added.
+    cell: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    // Total length of the lists in `cells`
+    entries: usize,
+    // Number of lines when the cell size was last chosen
+    sized_for: usize,
+}
+
+impl Grid {
+    // Lists the line now at index. `lines` already includes the new line.
+    pub(crate) fn insert(&mut self, lines: &[Vec<Point>], index: usize) {
+        if self.cell == 0.0 || lines.len() >= 2 * self.sized_for.max(32) {
+            self.rebuild(lines);
+            return;
+        }
+        let line = &lines[index];
+        if self.entries + cell_estimate(line, self.cell) > CELLS_PER_LINE * lines.len() + SPARE_CELLS {
+            self.rebuild(lines);
+            return;
+        }
+        self.add(line, index);
+    }
+
+    // Lists the lines from index `first` on, which were all just added to the end of `lines`
+    pub(crate) fn extend(&mut self, lines: &[Vec<Point>], first: usize) {
+        if self.cell == 0.0 || lines.len() >= 2 * self.sized_for.max(32) {
+            self.rebuild(lines);
+            return;
+        }
+        for (index, line) in lines.iter().enumerate().skip(first) {
+            if self.entries + cell_estimate(line, self.cell) > CELLS_PER_LINE * lines.len() + SPARE_CELLS {
+                self.rebuild(lines);
+                return;
+            }
+            self.add(line, index);
+        }
+    }
+
+    // Forgets the line that was at index
+    pub(crate) fn remove(&mut self, index: usize, line: &[Point]) {
+        for cell in covered_cells(line[0], line[1], self.cell) {
+            if let Some(list) = self.cells.get_mut(&cell) {
+                if let Some(position) = list.iter().position(|&i| i == index) {
+                    list.swap_remove(position);
+                    self.entries -= 1;
+                }
+                if list.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    // Lists the line that moved from index `from` to index `to` under its new index
+    pub(crate) fn relabel(&mut self, line: &[Point], from: usize, to: usize) {
+        for cell in covered_cells(line[0], line[1], self.cell) {
+            if let Some(i) = self.cells.get_mut(&cell).and_then(|list| list.iter_mut().find(|i| **i == from)) {
+                *i = to;
+            }
+        }
+    }
+
+    // Indices of the lines listed in the cells the segment a -> b passes through, in ascending order. These are the only
+    // lines that can touch it; callers still need to check them exactly.
+    pub(crate) fn near_segment(&self, a: Point, b: Point) -> Vec<usize> {
+        if self.cell == 0.0 {
+            return Vec::new();
+        }
+        // A segment much longer than the drawing passes through more cells than there are listed
+        if cell_estimate(&[a, b], self.cell) >= self.cells.len() {
+            return self.collect(self.cells.keys().copied().collect());
+        }
+        self.collect(covered_cells(a, b, self.cell))
+    }
+
+    // Indices of the lines listed in the cells overlapping the box, in ascending order
+    pub(crate) fn near_box(&self, min: Point, max: Point) -> Vec<usize> {
+        if self.cell == 0.0 {
+            return Vec::new();
+        }
+        let (x_low, x_high) = cell_range(min.0, max.0, self.cell);
+        let (y_low, y_high) = cell_range(min.1, max.1, self.cell);
+        // A box much bigger than the drawing covers more cells than there are listed
+        if x_high.saturating_sub(x_low).saturating_mul(y_high.saturating_sub(y_low)) >= self.cells.len() as i64 {
+            return self.collect(self.cells.keys().copied().collect());
+        }
+        let mut cells = Vec::new();
+        for x in x_low..=x_high {
+            for y in y_low..=y_high {
+                cells.push((x, y));
+            }
+        }
+        self.collect(cells)
+    }
+
+    fn collect(&self, cells: Vec<(i64, i64)>) -> Vec<usize> {
+        let mut found: Vec<usize> = cells.iter().filter_map(|cell| self.cells.get(cell)).flatten().copied().collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    fn add(&mut self, line: &[Point], index: usize) {
+        for cell in covered_cells(line[0], line[1], self.cell) {
+            self.cells.entry(cell).or_default().push(index);
+            self.entries += 1;
+        }
+    }
+
+    // Lists every line again, with cells about as large as the average line, grown until the lines fit the cell budget
+    fn rebuild(&mut self, lines: &[Vec<Point>]) {
+        self.cells.clear();
+        self.entries = 0;
+        self.sized_for = lines.len();
+        if lines.is_empty() {
+            self.cell = 0.0;
+            return;
+        }
+        let total: f64 = lines.iter().map(|line| extent(line)).sum();
+        let mut cell = (total / lines.len() as f64).max(f64::MIN_POSITIVE);
+        while lines.iter().map(|line| cell_estimate(line, cell)).sum::<usize>() > CELLS_PER_LINE * lines.len() + SPARE_CELLS {
+            cell *= 2.0;
+        }
+        self.cell = cell;
+        for (index, line) in lines.iter().enumerate() {
+            self.add(line, index);
+        }
+    }
+}
+
+// Largest of a line's width and height
+fn extent(line: &[Point]) -> f64 {
+    (line[1].0 - line[0].0).abs().max((line[1].1 - line[0].1).abs())
+}
+
+// Upper bound on how many cells a line passes through
+fn cell_estimate(line: &[Point], cell: f64) -> usize {
+    let steps = ((line[1].0 - line[0].0).abs() + (line[1].1 - line[0].1).abs()) / cell;
+    if steps < (usize::MAX / 4) as f64 { steps as usize + 4 } else { usize::MAX / 4 }
+}
+
+// Range of cells covering low..=high along one axis, widened by the padding
+fn cell_range(low: f64, high: f64, cell: f64) -> (i64, i64) {
+    ((low / cell - PADDING).floor() as i64, (high / cell + PADDING).floor() as i64)
+}
+
+// Cells the segment a -> b passes through, column by column
+fn covered_cells(a: Point, b: Point, cell: f64) -> Vec<(i64, i64)> {
+    let (left, right) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+    let (first_column, last_column) = cell_range(left.0, right.0, cell);
+    let slope = if right.0 > left.0 { (right.1 - left.1) / (right.0 - left.0) } else { 0.0 };
+    let mut cells = Vec::new();
+    for column in first_column..=last_column {
+        // The part of the segment inside this column
+        let (low_y, high_y) = if right.0 > left.0 {
+            let x1 = (column as f64 * cell).clamp(left.0, right.0);
+            let x2 = ((column + 1) as f64 * cell).clamp(left.0, right.0);
+            let (y1, y2) = (left.1 + (x1 - left.0) * slope, left.1 + (x2 - left.0) * slope);
+            (y1.min(y2), y1.max(y2))
+        } else {
+            (left.1.min(right.1), left.1.max(right.1))
+        };
+        let (low_row, high_row) = cell_range(low_y, high_y, cell);
+        for row in low_row..=high_row {
+            cells.push((column, row));
+        }
+    }
+    cells
+}
+
+// Distance from p to the segment a -> b, and the nearest point on the segment
+pub(crate) fn segment_distance(p: Point, a: Point, b: Point) -> (f64, Point) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 { (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+    let nearest = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - nearest.0).hypot(p.1 - nearest.1), nearest)
+}
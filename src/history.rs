@@ -0,0 +1,88 @@
+// Undo/redo history for ShapeBuilder.
+//
+// Every change to the stored lines goes through ShapeBuilder::insert_line or ShapeBuilder::remove_line, which record an
+// Edit here. That includes the splits and merges add_line makes to existing lines, so undoing a line also restores the
+// lines it cut. Edits are grouped into transactions, one per user action, and undone or redone a transaction at a time.
+// Part labels are otherwise outside the history, but a transform of the whole drawing moves them along with the lines,
+// so that move is recorded too.
+
+// A single change to the stored lines, with enough information to apply it again or reverse it
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Edit {
+    Insert { index: usize, line: Vec<(f64, f64)> },
+    Remove { index: usize, line: Vec<(f64, f64)> },
+    // The label of the part with this name moved from one point to the other
+    MovePart { name: String, from: (f64, f64), to: (f64, f64) },
+}
+
+// All the edits made by one user action, in the order they were made
+pub(crate) type Transaction = Vec<Edit>;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct History {
+    undo: Vec<Transaction>,
+    redo: Vec<Transaction>,
+    // Edits of the transaction currently being recorded
+    open: Transaction,
+    // Length of `open` when each nested transaction began, innermost last
+    marks: Vec<usize>,
+}
+
+impl History {
+    // Starts a transaction. Transactions nest, e.g. add_rect runs four add_line transactions inside its own, and only the
+    // outermost one ends up on the undo stack.
+    pub(crate) fn begin(&mut self) {
+        self.marks.push(self.open.len());
+    }
+
+    // Ends the innermost transaction. Closing the outermost one makes it undoable and forgets anything that was undone.
+    pub(crate) fn end(&mut self) {
+        self.marks.pop();
+        self.close_if_outermost();
+    }
+
+    // Ends the innermost transaction without keeping it, returning its edits (most recent last) so they can be reversed
+    pub(crate) fn abort(&mut self) -> Transaction {
+        let mark = self.marks.pop().unwrap_or(0);
+        let edits = self.open.split_off(mark);
+        self.close_if_outermost();
+        edits
+    }
+
+    fn close_if_outermost(&mut self) {
+        if self.marks.is_empty() && !self.open.is_empty() {
+            self.undo.push(std::mem::take(&mut self.open));
+            self.redo.clear();
+        }
+    }
+
+    pub(crate) fn record(&mut self, edit: Edit) {
+        self.open.push(edit);
+    }
+
+    pub(crate) fn in_transaction(&self) -> bool {
+        !self.marks.is_empty()
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Takes the most recent transaction off the undo stack and remembers it for redo
+    pub(crate) fn pop_undo(&mut self) -> Option<Transaction> {
+        let transaction = self.undo.pop()?;
+        self.redo.push(transaction.clone());
+        Some(transaction)
+    }
+
+    // Takes the most recently undone transaction off the redo stack and makes it undoable again
+    pub(crate) fn pop_redo(&mut self) -> Option<Transaction> {
+        let transaction = self.redo.pop()?;
+        self.undo.push(transaction.clone());
+        Some(transaction)
+    }
+}
@@ -1,7 +1,53 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
+use js_sys::{Float64Array, Uint32Array};
 use serde::{Serialize, Deserialize};
 use serde_wasm_bindgen::to_value;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use analysis::{Analysis, Cache};
+use arrangement::Meeting;
+use batch::Batch;
+use boolean::Operation;
+use buffers::Packed;
+use document::Document;
+use grid::Grid;
+use history::{Edit, History};
+use transform::Affine;
+use trim::Run;
+use vertices::Vertices;
+
+mod analysis;
+mod arrangement;
+mod batch;
+mod boolean;
+mod buffers;
+mod corners;
+mod csv;
+mod curves;
+mod document;
+mod dxf;
+mod error;
+mod format;
+mod geo;
+mod grid;
+mod history;
+mod loops;
+mod offset;
+mod pick;
+mod section;
+mod svg;
+mod transform;
+mod trim;
+mod vertices;
+
+pub use document::Part;
+pub use error::ShapeError;
+pub use format::decimals;
+pub use loops::Loop;
+pub use offset::Join;
+pub use pick::{Pick, PickKind};
+pub use section::SectionProperties;
 
 // Logging functions for Wasm targets. Pushes error messages to web console via Javascript.
 #[cfg(target_arch = "wasm32")]
@@ -41,95 +87,300 @@ fn log_f64(a: f64) {
 // Core code for creating and storing shapes
 #[wasm_bindgen]
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(try_from = "SavedShape")]
 pub struct ShapeBuilder {
     lines: Vec<Vec<(f64, f64)>>,
+    #[serde(skip)]
+    history: History,
+    #[serde(skip)]
+    batch: Option<Batch>,
+    #[serde(skip)]
+    grid: Grid,
+    #[serde(skip)]
+    vertices: Vertices,
+    // Changes whenever the lines do
+    #[serde(skip)]
+    version: u32,
+    #[serde(skip)]
+    packed: Packed,
+    #[serde(skip)]
+    analysis: Cache,
+    // Saved with the drawing by to_json, but not part of the geometry or the undo history
+    #[serde(skip)]
+    units: String,
+    #[serde(skip)]
+    metadata: BTreeMap<String, String>,
+    #[serde(skip)]
+    parts: Vec<Part>,
+}
+
+// What serde reads back: only the lines are stored, everything else is rebuilt from them
+#[derive(Deserialize)]
+struct SavedShape {
+    lines: Vec<Vec<(f64, f64)>>,
+}
+
+// The lines are added like a batch, so they get add_line's checks and a hand-edited file with crossing lines is split as
+// usual. A malformed line is an error rather than a panic later on.
+impl TryFrom<SavedShape> for ShapeBuilder {
+    type Error = ShapeError;
+
+    fn try_from(saved: SavedShape) -> Result<Self, ShapeError> {
+        let segments = saved.lines.iter().enumerate().map(|(i, line)| match line.as_slice() {
+            &[(x1, y1), (x2, y2)] => Ok([x1, y1, x2, y2]),
+            _ => Err(ShapeError::Parse { line: None, message: format!("line {} has {} points instead of 2", i, line.len()) }),
+        }).collect::<Result<Vec<_>, _>>()?;
+        ShapeBuilder::opened(&segments)
+    }
+}
+
+impl Default for ShapeBuilder {
+    fn default() -> Self {
+        ShapeBuilder::new()
+    }
 }
 
 #[wasm_bindgen]
 impl ShapeBuilder {    
     // Create new instance
     pub fn new() -> ShapeBuilder {
-        ShapeBuilder {lines: Vec::new()}
+        ShapeBuilder {
+            lines: Vec::new(),
+            history: History::default(),
+            batch: None,
+            grid: Grid::default(),
+            vertices: Vertices::default(),
+            version: 0,
+            packed: Packed::default(),
+            analysis: Cache::default(),
+            units: String::new(),
+            metadata: BTreeMap::new(),
+            parts: Vec::new(),
+        }
+    }
+
+    // Saves the drawing, with its units, metadata and parts, as a JSON document (see document.rs for the format)
+    pub fn to_json(&self) -> String {
+        Document {
+            format: document::FORMAT.to_string(),
+            version: document::CURRENT_VERSION,
+            units: self.units.clone(),
+            metadata: self.metadata.clone(),
+            parts: self.parts.clone(),
+            segments: self.lines.iter().map(|line| [line[0].0, line[0].1, line[1].0, line[1].1]).collect(),
+        }.to_json()
+    }
+
+    // Opens a drawing saved by to_json, including documents saved by older versions. The segments are added like a batch,
+    // so a hand-edited document with crossing lines is split as usual. The new shape starts with an empty undo history.
+    pub fn from_json(json: &str) -> Result<ShapeBuilder, ShapeError> {
+        let document = Document::from_json(json)?;
+        let mut shape = ShapeBuilder::opened(&document.segments)?;
+        shape.units = document.units;
+        shape.metadata = document.metadata;
+        shape.parts = document.parts;
+        Ok(shape)
+    }
+
+    // Draws the lines as an SVG figure for reports: enclosed regions filled with holes left empty, and the centroid,
+    // principal axes and overall dimensions marked and labelled (see svg.rs). Lines that don't enclose anything are drawn
+    // without the fill and the centroid and axes.
+    pub fn to_svg(&self) -> String {
+        let analysis = self.analysis().ok();
+        svg::write(&self.lines, analysis.as_deref(), &self.units)
+    }
+
+    // Writes the drawing as a DXF R12 file for CAD, with the centroid, principal axes and section properties on their own
+    // layers (see dxf.rs). Lines that don't enclose anything are written without them.
+    pub fn to_dxf(&self) -> String {
+        let analysis = self.analysis().ok();
+        dxf::write(&self.lines, analysis.as_deref(), &self.units)
+    }
+
+    // The enclosed region as Well-Known Text: a POLYGON with its holes, a MULTIPOLYGON if it has separate pieces, or
+    // POLYGON EMPTY if the lines don't enclose anything. Faces sharing an edge are merged (see geo.rs).
+    pub fn to_wkt(&self) -> String {
+        geo::write_wkt(self.analysis().ok().as_deref())
+    }
+
+    // The enclosed region as a GeoJSON Feature, with its area, centroid and units as properties
+    pub fn to_geojson(&self) -> String {
+        geo::write_geojson(self.analysis().ok().as_deref(), &self.units)
+    }
+
+    // Unit of length the coordinates are in, e.g. "mm". Empty if not set.
+    pub fn units(&self) -> String {
+        self.units.clone()
+    }
+
+    pub fn set_units(&mut self, units: &str) {
+        self.units = units.to_string();
+    }
+
+    // Free-form information saved with the drawing, such as a project name or author
+    pub fn get_metadata(&self, key: &str) -> Option<String> {
+        self.metadata.get(key).cloned()
+    }
+
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.insert(key.to_string(), value.to_string());
+    }
+
+    // Returns false if there was no such key
+    pub fn remove_metadata(&mut self, key: &str) -> bool {
+        self.metadata.remove(key).is_some()
+    }
+
+    // Names the part of the drawing around (x, y). Naming a part again moves its label.
+    pub fn add_part(&mut self, name: &str, x: f64, y: f64) -> Result<(), ShapeError> {
+        if !x.is_finite() || !y.is_finite() {
+            return Err(ShapeError::NaNInput);
+        }
+        let part = Part { name: name.to_string(), at: (x, y) };
+        match self.parts.iter_mut().find(|part| part.name == name) {
+            Some(existing) => *existing = part,
+            None => self.parts.push(part),
+        }
+        Ok(())
+    }
+
+    // Returns false if there was no part with that name
+    pub fn remove_part(&mut self, name: &str) -> bool {
+        let count = self.parts.len();
+        self.parts.retain(|part| part.name != name);
+        self.parts.len() != count
+    }
+
+    pub fn part_names(&self) -> Vec<String> {
+        self.parts.iter().map(|part| part.name.clone()).collect()
     }
 
     // Deletes a given line from the shape
     pub fn delete_line(&mut self, a1x: f64, a1y: f64, a2x: f64, a2y: f64) {
-        let oldLineA = vec![(a1x, a1y), (a2x, a2y)];
-        let oldLineB = vec![(a2x, a2y), (a1x, a1y)];
-        self.lines.retain(|line| *line != oldLineA);
-        self.lines.retain(|line| *line != oldLineB);
+        let old_line_a = vec![(a1x, a1y), (a2x, a2y)];
+        let old_line_b = vec![(a2x, a2y), (a1x, a1y)];
+        if let Some(batch) = self.batch.as_mut() {
+            batch.remove(&old_line_a, &old_line_b);
+        }
+        self.history.begin();
+        for index in self.grid.near_segment((a1x, a1y), (a2x, a2y)).into_iter().rev() {
+            if self.lines[index] == old_line_a || self.lines[index] == old_line_b {
+                self.remove_line(index);
+            }
+        }
+        self.history.end();
+    }
+
+    // Deletes the line with the given edge ID (its index in the stored lines, e.g. from pick)
+    pub fn delete_line_by_id(&mut self, id: usize) -> Result<(), ShapeError> {
+        let line = self.lines.get(id).ok_or(ShapeError::InvalidId)?;
+        let ((a1x, a1y), (a2x, a2y)) = (line[0], line[1]);
+        self.delete_line(a1x, a1y, a2x, a2y);
+        Ok(())
+    }
+
+    // Finds the geometry under (x, y): the nearest vertex within radius, or failing that the nearest edge within radius.
+    // Vertices win over edges so that a line's endpoints can still be picked where the line itself is closer.
+    pub fn pick(&self, x: f64, y: f64, radius: f64) -> Option<Pick> {
+        if let Some((point, distance)) = self.nearest_vertex(x, y, radius) {
+            let id = self.vertex_id(point)?;
+            return Some(Pick { kind: PickKind::Vertex, id, distance, x: point.0, y: point.1 });
+        }
+        self.pick_edge(x, y, radius)
+    }
+
+    // Like pick, but only finds edges, e.g. for tools that act on whole lines
+    pub fn pick_edge(&self, x: f64, y: f64, radius: f64) -> Option<Pick> {
+        let (id, _) = self.nearest_line(x, y, radius)?;
+        let (distance, (px, py)) = grid::segment_distance((x, y), self.lines[id][0], self.lines[id][1]);
+        Some(Pick { kind: PickKind::Edge, id, distance, x: px, y: py })
+    }
+
+    // Number that changes whenever the lines change (including undo and redo), so Javascript can skip re-reading geometry
+    // it already has
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    // Coordinates of every vertex as [x0, y0, x1, y1, ...], in vertex ID order. This is a view straight into Wasm memory,
+    // not a copy: read or copy it before calling into the module again, as any call that allocates can move it.
+    pub fn vertex_buffer(&mut self) -> Float64Array {
+        self.packed.update(&self.lines, &self.vertices, self.version);
+        // Safe as long as Javascript follows the rule above; the buffer isn't touched again until the next call
+        unsafe { Float64Array::view(&self.packed.coordinates) }
+    }
+
+    // Vertex IDs of the two ends of every edge as [start0, end0, start1, end1, ...], in edge ID order. Also a view into
+    // Wasm memory, with the same rule as vertex_buffer.
+    pub fn edge_buffer(&mut self) -> Uint32Array {
+        self.packed.update(&self.lines, &self.vertices, self.version);
+        unsafe { Uint32Array::view(&self.packed.edges) }
     }
 
     // Add a line to the Shape, handles intersection as well 
-    pub fn add_line(&mut self, mut start_x: f64, mut start_y: f64, mut end_x: f64, mut end_y: f64) {
+    pub fn add_line(&mut self, mut start_x: f64, mut start_y: f64, mut end_x: f64, mut end_y: f64) -> Result<(), ShapeError> {
         //With the way line intersection works, which is way more complicated than we anticipated, a check for collinearity and resolving any instances of that followed by a check for intersection and resolving of that. Like dude think about how many edge cases there are it's actually insane
         let mut lines_to_delete = Vec::new();
         let mut lines_to_add = Vec::new();
-        let mut lines_to_process: Vec<Vec<(f64, f64)>> = Vec::new(); // Track lines to be processed
 
-        // Start collinearity and intersection detection
-        for line in &self.lines {
+        if ![start_x, start_y, end_x, end_y].iter().all(|c| c.is_finite()) {
+            return self.reject(ShapeError::NaNInput);
+        }
+        // A single point is not a line
+        if start_x == end_x && start_y == end_y {
+            return self.reject(ShapeError::Degenerate);
+        }
+        // Inside a batch, intersections are resolved when the batch is committed
+        if let Some(batch) = self.batch.as_mut() {
+            return batch.queue(vec![(start_x, start_y), (end_x, end_y)]);
+        }
 
-            if line.len() < 2 {
-                continue;  // Skip lines that don't have two points
-            } 
+        // Everything below, including splits and merges of existing lines, is undone together
+        self.history.begin();
 
-            let b1x: f64 = line[0].0;
-            let b1y: f64 = line[0].1;
-            let b2x: f64 = line[1].0;
-            let b2y: f64 = line[1].1;
+        // Start collinearity detection. Every existing line on the same infinite line whose extent overlaps or touches the
+        // new one is absorbed into it. Growing the new line can make it reach a line that was already passed over, so keep
+        // scanning until a full pass merges nothing.
+        loop {
+            let mut absorbed = None;
+            for index in self.grid.near_segment((start_x, start_y), (end_x, end_y)) {
+                let line = &self.lines[index];
+                if line.len() < 2 {
+                    continue;  // Skip lines that don't have two points
+                }
 
-            let mut cx: f64 = f64::INFINITY;
-            let mut cy: f64 = f64::INFINITY;
-            
-            get_intersection(start_x, start_y, end_x, end_y, b1x, b1y, b2x, b2y, &mut cx, &mut cy);
-            
-            if cx == f64::NEG_INFINITY && cy == f64::NEG_INFINITY {
-                let mut longest_x = 0.0;
-                let mut longest_y = 0.0;
-                let mut d1x = 0.0;
-                let mut d1y = 0.0;
-                let mut d2x = 0.0;
-                let mut d2y = 0.0;
-
-                if (start_x - b1x).abs() >= longest_x && (start_y - b1y).abs() >= longest_y {
-                    d1x = start_x;
-                    d1y = start_y;
-                    d2x = b1x;
-                    d2y = b1y;
-                    longest_x = (d1x - d2x).abs();
-                    longest_y = (d1y - d2y).abs();
+                let mut cx: f64 = f64::INFINITY;
+                let mut cy: f64 = f64::INFINITY;
+
+                get_intersection(start_x, start_y, end_x, end_y, line[0].0, line[0].1, line[1].0, line[1].1, &mut cx, &mut cy);
+
+                if cx == f64::NEG_INFINITY && cy == f64::NEG_INFINITY {
+                    if let Some(union) = collinear_union((start_x, start_y), (end_x, end_y), line[0], line[1]) {
+                        absorbed = Some((line.clone(), union));
+                        break;
+                    }
                 }
-                if (end_x - b1x).abs() >= longest_x && (end_y - b1y).abs() >= longest_y {
-                    d1x = end_x;
-                    d1y = end_y;
-                    d2x = b1x;
-                    d2y = b1y;
-                    longest_x = (d1x - d2x).abs();
-                    longest_y = (d1y - d2y).abs();
+            }
+            match absorbed {
+                Some((line, ((d1x, d1y), (d2x, d2y)))) => {
+                    //gets rid of old line
+                    self.delete_line(line[0].0, line[0].1, line[1].0, line[1].1);
+                    start_x = d1x;
+                    start_y = d1y;
+                    end_x = d2x;
+                    end_y = d2y;
                 }
-               
-                //gets rid of old lines
-                lines_to_delete.push(vec![(start_x, start_y), (end_x, end_y)]);
-                lines_to_delete.push(vec![(b1x, b1y), (b2x, b2y)]);
-                start_x = d1x;
-                start_y = d1y;
-                end_x = d2x;
-                end_y = d2y;
+                None => break,
             }
         }
-        for line in lines_to_delete {
-            self.delete_line(line[0].0, line[0].1, line[1].0, line[1].1);
-        }
-        lines_to_delete = vec![];
-        lines_to_add = vec![];
 
         //Checks for intersections
-        let mut noInt = true;
+        let mut no_int = true;
         let mut intersections = Vec::new(); //Track where intersections lie on original line
         let mut lines_split = Vec::new(); // Track which lines have been split
         intersections.push((start_x, start_y));
-        for line in self.lines.iter() {
+        for index in self.grid.near_segment((start_x, start_y), (end_x, end_y)) {
+            let line = &self.lines[index];
             let b1x = line[0].0;
             let b1y = line[0].1;
             let b2x = line[1].0;
@@ -140,16 +391,16 @@ impl ShapeBuilder {
             
             get_intersection(start_x, start_y, end_x, end_y, b1x, b1y, b2x, b2y, &mut cx, &mut cy);
             
-            if cx != f64::INFINITY && cx != f64::NEG_INFINITY && cy != f64::INFINITY && cy != f64::NEG_INFINITY { 
+            if cx.is_finite() && cy.is_finite() { 
                 //intersection!
-                noInt = false;
+                no_int = false;
                 intersections.push((cx, cy));
                 lines_split.push((b1x, b1y, b2x, b2y, cx, cy));
             }
         }
 
         //no intersections
-        if noInt {
+        if no_int {
             lines_to_add.push(vec![(start_x, start_y), (end_x, end_y)]);
         }
         else {
@@ -164,18 +415,16 @@ impl ShapeBuilder {
             lines_to_delete.push(vec![(start_x, start_y), (end_x, end_y)]);
             intersections.push((end_x, end_y));
             if start_x < end_x {
-                intersections.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("REASON"));
+                intersections.sort_by(|a, b| a.0.total_cmp(&b.0));
             }
             else if end_x < start_x{
-                intersections.sort_by(|a, b| b.0.partial_cmp(&a.0).expect("REASON"));
+                intersections.sort_by(|a, b| b.0.total_cmp(&a.0));
+            }
+            else if start_y < end_y {
+                intersections.sort_by(|a, b| a.1.total_cmp(&b.1));
             }
             else {
-                if start_y < end_y {
-                    intersections.sort_by(|a, b| a.1.partial_cmp(&b.1).expect("REASON"));
-                }
-                else {
-                    intersections.sort_by(|a, b| b.1.partial_cmp(&a.1).expect("REASON"));
-                }
+                intersections.sort_by(|a, b| b.1.total_cmp(&a.1));
             }
             for i in 0..(intersections.len() - 1) {
                 lines_to_add.push(vec![(intersections[i].0, intersections[i].1), (intersections[i+1].0, intersections[i+1].1)]);
@@ -189,45 +438,375 @@ impl ShapeBuilder {
         }
         // Makes sure no points or duplicate lines are added
         for line in &lines_to_add {
-            if (line[0].0 != line[1].0 || line[0].1 != line[1].1) && !self.lines.contains(&vec![(line[0].0, line[0].1),(line[1].0, line[1].1)]) && !self.lines.contains(&vec![(line[1].0, line[1].1),(line[0].0, line[0].1)]){
-                self.lines.push(line.clone());
+            if (line[0].0 != line[1].0 || line[0].1 != line[1].1) && !self.contains_line(line[0], line[1]) {
+                self.insert_line(line.clone());
             }
         }
-
+        self.history.end();
+        Ok(())
     }
 
     // add_rect: Given two vertices that are diagonally opposite in the rectangle, (top left and bottom right used for ease of design)
     // Calculates and adds a series of lines that make up said rectangle.
-    pub fn add_rect(&mut self, top_left_x: f64, top_left_y: f64, bottom_right_x: f64, bottom_right_y: f64) {
+    pub fn add_rect(&mut self, top_left_x: f64, top_left_y: f64, bottom_right_x: f64, bottom_right_y: f64) -> Result<(), ShapeError> {
+        if ![top_left_x, top_left_y, bottom_right_x, bottom_right_y].iter().all(|c| c.is_finite()) {
+            return self.reject(ShapeError::NaNInput);
+        }
+        // A rectangle without width or height would leave stray lines behind
+        if top_left_x == bottom_right_x || top_left_y == bottom_right_y {
+            return self.reject(ShapeError::Degenerate);
+        }
+
         let top_right_x = bottom_right_x;
         let top_right_y = top_left_y;
         let bottom_left_x = top_left_x;
         let bottom_left_y = bottom_right_y;
 
-        self.add_line(top_left_x, top_left_y, top_right_x, top_right_y);
-        self.add_line(top_right_x, top_right_y, bottom_right_x, bottom_right_y);
-        self.add_line(bottom_right_x, bottom_right_y, bottom_left_x, bottom_left_y);
-        self.add_line(bottom_left_x, bottom_left_y, top_left_x, top_left_y);
+        self.transaction(|shape| {
+            shape.add_line(top_left_x, top_left_y, top_right_x, top_right_y)?;
+            shape.add_line(top_right_x, top_right_y, bottom_right_x, bottom_right_y)?;
+            shape.add_line(bottom_right_x, bottom_right_y, bottom_left_x, bottom_left_y)?;
+            shape.add_line(bottom_left_x, bottom_left_y, top_left_x, top_left_y)
+        })
     }
-    
-    // Calculate the centroid for closed shapes
-    pub fn calculate_centroid(&self) -> JsValue {
-        let num_points = self.lines.len();
-        if num_points < 3 {
-            return JsValue::NULL; // Cannot calculate centroid for less than 3 points.
-        }
-
-        // Flatten all points from lines
-        let points: Vec<(f64, f64)> = self.lines.iter().flat_map(|line| line.iter()).cloned().collect();
-        
-        // Calculate the average x and y for the centroid of the shape 
-        let (sum_x, sum_y) = points.iter().fold((0.0, 0.0), |(sum_x, sum_y), &(x, y)| {
-            (sum_x + x, sum_y + y)
-        });
-        
-        let centroid = (sum_x / points.len() as f64, sum_y / points.len() as f64);
-        
-        to_value(&centroid).unwrap() // Serialize the centroid to JsValue
+
+    // Adds a polyline through the points, given as [x0, y0, x1, y1, ...], and back to the first point when closed. Repeated
+    // points are skipped, as is a closed polygon's first point repeated at its end. Goes through the batch path, so edges
+    // crossing each other or the drawing are split as usual, in one undo step; if anything fails, nothing is added.
+    // Degenerate for an odd number of coordinates, or fewer than 2 distinct points (3 when closed).
+    pub fn add_polygon(&mut self, points: &[f64], closed: bool) -> Result<(), ShapeError> {
+        self.check_finite(points)?;
+        if !points.len().is_multiple_of(2) {
+            return self.reject(ShapeError::Degenerate);
+        }
+        let mut vertices: Vec<(f64, f64)> = points.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect();
+        vertices.dedup();
+        if closed && vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        if vertices.len() < if closed { 3 } else { 2 } {
+            return self.reject(ShapeError::Degenerate);
+        }
+        if closed {
+            vertices.push(vertices[0]);
+        }
+        let segments: Vec<[f64; 4]> = vertices.windows(2).map(|pair| [pair[0].0, pair[0].1, pair[1].0, pair[1].1]).collect();
+        self.add_segments(&segments)
+    }
+
+    // Adds the outlines drawn in an SVG file (see svg.rs for what is read). Curves, circles and ellipses become straight
+    // segments within tolerance of the curve, in SVG user units. Goes through the batch path, so crossings are split as
+    // usual and the whole import is one undo step; if anything fails, nothing is added.
+    pub fn import_svg(&mut self, svg: &str, tolerance: f64) -> Result<(), ShapeError> {
+        self.check_tolerance(tolerance)?;
+        match svg::parse(svg, tolerance) {
+            Ok(segments) => self.add_segments(&segments),
+            Err(error) => self.reject(error),
+        }
+    }
+
+    // Adds the LINE, LWPOLYLINE, ARC and CIRCLE entities of an ASCII DXF file (see dxf.rs), from the given layers or from
+    // every layer if none are given. Arcs become straight segments within tolerance. If the file's $INSUNITS names a
+    // unit, a drawing without units takes it on, and a drawing in another unit gets the geometry converted to its own.
+    // Like import_svg, this is one undo step and adds nothing if it fails.
+    pub fn import_dxf(&mut self, dxf: &str, layers: Vec<String>, tolerance: f64) -> Result<(), ShapeError> {
+        self.check_tolerance(tolerance)?;
+        let mut drawing = match dxf::parse(dxf, &layers, tolerance) {
+            Ok(drawing) => drawing,
+            Err(error) => return self.reject(error),
+        };
+        if let Some(units) = drawing.units.filter(|&units| !self.units.is_empty() && units != self.units) {
+            let scale = match dxf::unit_scale(units, &self.units) {
+                Some(scale) => scale,
+                None => return self.reject(ShapeError::Parse {
+                    line: Some(drawing.units_line),
+                    message: format!("can't convert the file's units ({}) to the drawing's ({})", units, self.units),
+                }),
+            };
+            for segment in drawing.segments.iter_mut() {
+                segment.iter_mut().for_each(|coordinate| *coordinate *= scale);
+            }
+        }
+        self.add_segments(&drawing.segments)?;
+        if let (true, Some(units)) = (self.units.is_empty(), drawing.units) {
+            self.units = units.to_string();
+        }
+        Ok(())
+    }
+
+    // Adds the rings of a POLYGON or MULTIPOLYGON in Well-Known Text, holes included (see geo.rs). Like import_svg, this
+    // is one undo step and adds nothing if it fails.
+    pub fn import_wkt(&mut self, wkt: &str) -> Result<(), ShapeError> {
+        match geo::parse_wkt(wkt) {
+            Ok(segments) => self.add_segments(&segments),
+            Err(error) => self.reject(error),
+        }
+    }
+
+    // Adds the rings of the Polygons and MultiPolygons in a GeoJSON geometry, Feature or FeatureCollection
+    pub fn import_geojson(&mut self, geojson: &str) -> Result<(), ShapeError> {
+        match geo::parse_geojson(geojson) {
+            Ok(segments) => self.add_segments(&segments),
+            Err(error) => self.reject(error),
+        }
+    }
+
+    // Adds the rings of a plain-text coordinate list, one x,y per line and a blank line between rings, each closed into a
+    // loop (see csv.rs for the delimiters, headers and comments it understands). Like import_svg, this is one undo step
+    // and adds nothing if it fails; parse errors give the line they're on.
+    pub fn import_csv(&mut self, text: &str) -> Result<(), ShapeError> {
+        match csv::parse(text) {
+            Ok(segments) => self.add_segments(&segments),
+            Err(error) => self.reject(error),
+        }
+    }
+
+    // Moves the vertex with the given ID (as from pick) to (x, y), dragging the end of every edge that meets there with it.
+    // The moved edges are resolved against the rest of the drawing, so they're split where they now cross other edges and
+    // merged where they now overlap them, and dropping the vertex onto another vertex joins the two. An edge whose other
+    // end is at (x, y) shrinks to nothing and is removed. One undo step; does nothing if it fails.
+    pub fn move_vertex(&mut self, id: usize, x: f64, y: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[x, y])?;
+        let from = match self.vertices.points().get(id) {
+            Some(&point) => point,
+            None => return self.reject(ShapeError::InvalidId),
+        };
+        if from == (x, y) {
+            return Ok(());
+        }
+        let key = loops::point_key(from);
+        let incident = trim::incident(&self.lines, &self.grid, from);
+        let moved: Vec<Vec<(f64, f64)>> = incident.iter()
+            .map(|&index| self.lines[index].iter().map(|&point| if loops::point_key(point) == key { (x, y) } else { point }).collect())
+            .filter(|line: &Vec<(f64, f64)>| line[0] != line[1])
+            .collect();
+        self.transaction(|shape| {
+            for &index in incident.iter().rev() {
+                shape.remove_line(index);
+            }
+            shape.insert_resolved_lines(moved);
+            Ok(())
+        })
+    }
+
+    // Rounds off the corner at the vertex with the given ID (as from pick) with an arc of the given radius, tangent to both
+    // edges, e.g. for the root fillets of rolled sections. The arc is drawn as straight pieces within radius / 10000 of the
+    // true arc. Degenerate unless exactly two edges meet at the vertex at an angle, with room on both for the arc; an arc
+    // that takes up a whole edge ends at its far end. Like move_vertex, the new edges are resolved against the rest of the
+    // drawing, in one undo step, and nothing changes if it fails.
+    pub fn fillet(&mut self, id: usize, radius: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[radius])?;
+        if radius <= 0.0 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        let (edges, [corner, a, b]) = match self.corner(id) {
+            Ok(found) => found,
+            Err(error) => return self.reject(error),
+        };
+        match corners::fillet(corner, a, b, radius) {
+            Some(outline) => self.replace_corner(edges, outline),
+            None => self.reject(ShapeError::Degenerate),
+        }
+    }
+
+    // Cuts the corner at the vertex with the given ID straight across, from distance_1 along one edge to distance_2 along
+    // the other. distance_1 is measured along the edge with the lower edge ID. Fails as fillet does.
+    pub fn chamfer(&mut self, id: usize, distance_1: f64, distance_2: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[distance_1, distance_2])?;
+        if distance_1 <= 0.0 || distance_2 <= 0.0 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        let (edges, [corner, a, b]) = match self.corner(id) {
+            Ok(found) => found,
+            Err(error) => return self.reject(error),
+        };
+        match corners::chamfer(corner, a, b, distance_1, distance_2) {
+            Some(outline) => self.replace_corner(edges, outline),
+            None => self.reject(ShapeError::Degenerate),
+        }
+    }
+
+    // Removes part of a line, e.g. the stub left where it overshoots a corner. Lines are split where others cross or touch
+    // them, so a line drawn across others is stored as edges in a row. The edge with the given ID picks the line, and
+    // (x, y) picks the part of it to remove: the edges between the vertices either side of the point where other edges
+    // meet the line, or the line's free ends. The point is projected onto the line. One undo step.
+    pub fn trim(&mut self, id: usize, x: f64, y: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[x, y])?;
+        if id >= self.lines.len() {
+            return self.reject(ShapeError::InvalidId);
+        }
+        let edges = Run::through(&self.lines, &self.grid, id).span((x, y));
+        self.transaction(|shape| {
+            for &index in edges.iter().rev() {
+                shape.remove_line(index);
+            }
+            Ok(())
+        })
+    }
+
+    // Lengthens the line through the edge with the given ID straight on until it meets the boundary edge, from whichever
+    // end is nearer the boundary. The new piece is resolved against the rest of the drawing, so it splits the boundary and
+    // any edges it crosses on the way. NoIntersection if the line is parallel to the boundary, would pass beside it, or
+    // already reaches it. One undo step.
+    pub fn extend(&mut self, id: usize, boundary_id: usize) -> Result<(), ShapeError> {
+        let Some(boundary) = self.lines.get(boundary_id).filter(|_| id < self.lines.len()) else {
+            return self.reject(ShapeError::InvalidId);
+        };
+        match Run::through(&self.lines, &self.grid, id).extension(boundary[0], boundary[1]) {
+            Some(extension) => self.transaction(|shape| {
+                shape.insert_resolved_lines(vec![extension]);
+                Ok(())
+            }),
+            None => self.reject(ShapeError::NoIntersection),
+        }
+    }
+
+    // Splits the edge with the given ID in two at t along it (0 at its first point, 1 at its second), adding a vertex there
+    // that move_vertex can then drag. The two halves stay separate edges until an edit merges overlapping lines there
+    // again. Degenerate unless t is strictly between 0 and 1. One undo step.
+    pub fn split_edge(&mut self, id: usize, t: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[t])?;
+        let Some(line) = self.lines.get(id) else {
+            return self.reject(ShapeError::InvalidId);
+        };
+        let (start, end) = (line[0], line[1]);
+        let point = (start.0 + t * (end.0 - start.0), start.1 + t * (end.1 - start.1));
+        // A t very near 0 or 1 can round onto an end
+        if t <= 0.0 || t >= 1.0 || point == start || point == end {
+            return self.reject(ShapeError::Degenerate);
+        }
+        self.transaction(|shape| {
+            shape.remove_line(id);
+            shape.insert_line(vec![start, point]);
+            shape.insert_line(vec![point, end]);
+            Ok(())
+        })
+    }
+
+    // The transforms below move the edges with the given IDs, or the whole drawing when edges is empty. Moved edges are
+    // taken out and added back through the batch resolver, so wherever they now cross or overlap edges that stayed put,
+    // both are split and merged as if drawn there. Each call is one undo step and does nothing if it fails: InvalidId for
+    // an unknown edge, NaNInput for non-finite arguments. Edge IDs change afterwards, as for any edit.
+
+    // Moves edges by (dx, dy)
+    pub fn translate(&mut self, edges: Vec<usize>, dx: f64, dy: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[dx, dy])?;
+        self.transform_edges(edges, Affine::translation(dx, dy))
+    }
+
+    // Rotates edges counter-clockwise (with y up) by degrees about (center_x, center_y)
+    pub fn rotate(&mut self, edges: Vec<usize>, degrees: f64, center_x: f64, center_y: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[degrees, center_x, center_y])?;
+        self.transform_edges(edges, Affine::rotation(degrees, (center_x, center_y)))
+    }
+
+    // Scales edges by scale_x along x and scale_y along y, away from (center_x, center_y). Pass the same factor twice for a
+    // uniform scale; a negative factor mirrors. A zero factor would flatten the edges onto a line, so it's Degenerate.
+    pub fn scale(&mut self, edges: Vec<usize>, scale_x: f64, scale_y: f64, center_x: f64, center_y: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[scale_x, scale_y, center_x, center_y])?;
+        if scale_x == 0.0 || scale_y == 0.0 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        self.transform_edges(edges, Affine::scaling(scale_x, scale_y, (center_x, center_y)))
+    }
+
+    // Reflects edges in the infinite line through (x1, y1) and (x2, y2), which is Degenerate if they're the same point
+    pub fn mirror(&mut self, edges: Vec<usize>, x1: f64, y1: f64, x2: f64, y2: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[x1, y1, x2, y2])?;
+        if x1 == x2 && y1 == y2 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        self.transform_edges(edges, Affine::mirror((x1, y1), (x2, y2)))
+    }
+
+    // Arrays copy the edges with the given IDs, or the whole drawing when edges is empty, into a pattern such as a row of
+    // bolt holes or the openings of a castellated beam. The original stays where it is and counts as the first copy. Each
+    // copy is resolved against the drawing as if drawn there, in one undo step; copies that land on each other merge.
+    // Degenerate for a count of zero, otherwise failing as the transforms do.
+
+    // Copies edges into a grid of columns by rows, dx apart along x and dy apart along y
+    pub fn array_linear(&mut self, edges: Vec<usize>, columns: u32, rows: u32, dx: f64, dy: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[dx, dy])?;
+        if columns == 0 || rows == 0 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        let maps = (0..rows).flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .skip(1)
+            .map(|(column, row)| Affine::translation(column as f64 * dx, row as f64 * dy))
+            .collect();
+        self.copy_edges(edges, maps)
+    }
+
+    // Copies edges count times around (center_x, center_y), each copy turned counter-clockwise by degrees from the last,
+    // e.g. 6 copies 60 degrees apart for a bolt circle
+    pub fn array_polar(&mut self, edges: Vec<usize>, count: u32, center_x: f64, center_y: f64, degrees: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[center_x, center_y, degrees])?;
+        if count == 0 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        let maps = (1..count).map(|i| Affine::rotation(i as f64 * degrees, (center_x, center_y))).collect();
+        self.copy_edges(edges, maps)
+    }
+
+    // Boolean operations: replace this drawing with the region it encloses combined with the region other encloses, e.g.
+    // a plate welded to a beam, minus bolt holes. The result is drawn as its outlines and holes; lines that don't enclose
+    // anything are dropped. Coordinates are taken as they are, whatever the units. One undo step; does nothing if it
+    // fails: TouchingHole if the result has a hole touching its outline, which a drawing can't show.
+
+    // Everything in either region
+    pub fn union(&mut self, other: &ShapeBuilder) -> Result<(), ShapeError> {
+        self.combine(other, Operation::Union)
+    }
+
+    // This region with other's cut away
+    pub fn difference(&mut self, other: &ShapeBuilder) -> Result<(), ShapeError> {
+        self.combine(other, Operation::Difference)
+    }
+
+    // Only where the regions overlap
+    pub fn intersection(&mut self, other: &ShapeBuilder) -> Result<(), ShapeError> {
+        self.combine(other, Operation::Intersection)
+    }
+
+    // Everything in exactly one of the regions
+    pub fn xor(&mut self, other: &ShapeBuilder) -> Result<(), ShapeError> {
+        self.combine(other, Operation::Xor)
+    }
+
+    // Grows the solid region by distance, or shrinks it when distance is negative, e.g. for a corrosion allowance that
+    // takes t off every face. Corners are joined as join says; round joins stay within tolerance of a true arc. Holes
+    // shrink as the region grows and the other way round, and parts thinner than twice a negative distance disappear. The
+    // result replaces the drawing's lines as one undo step, as for the boolean operations above, and an offset of zero
+    // changes nothing.
+    pub fn offset(&mut self, distance: f64, join: Join, tolerance: f64) -> Result<(), ShapeError> {
+        self.check_finite(&[distance])?;
+        self.check_tolerance(tolerance)?;
+        if distance == 0.0 {
+            return Ok(());
+        }
+        let result = self.analysis().and_then(|analysis| offset::offset(&self.lines, &analysis.loops, distance, join, tolerance));
+        match result {
+            Ok(lines) => self.replace_lines(lines),
+            Err(error) => self.reject(error),
+        }
+    }
+
+    // Calculate the centroid for closed shapes, as an [x, y] array
+    pub fn calculate_centroid(&self) -> Result<JsValue, ShapeError> {
+        let centroid = self.centroid()?;
+        Ok(to_value(&centroid).unwrap_or(JsValue::NULL)) // Serialize the centroid to JsValue
+    }
+
+    // Net enclosed area, with holes subtracted
+    pub fn calculate_area(&self) -> Result<f64, ShapeError> {
+        self.area()
+    }
+
+    // Area, centroid, second moments of area (ixx, iyy, ixy) and principal axes (i_major, i_minor, principal_angle) as an
+    // object. Cached like the centroid, so it can be called on every mouse move.
+    pub fn calculate_section_properties(&self) -> Result<JsValue, ShapeError> {
+        let section = self.section_properties()?;
+        Ok(to_value(&section).unwrap_or(JsValue::NULL))
     }
 
     // Retrieve the lines (for visualization)
@@ -235,43 +814,512 @@ impl ShapeBuilder {
         to_value(&self.lines).unwrap_or(JsValue::NULL)
     }
 
-    // Clear the stored lines 
+    // Clear the stored lines (undoable like any other edit)
     pub fn clear(&mut self) {
-        self.lines.clear();
+        if let Some(batch) = self.batch.as_mut() {
+            batch.pending.clear();
+        }
+        self.history.begin();
+        while !self.lines.is_empty() {
+            self.remove_line(self.lines.len() - 1);
+        }
+        self.history.end();
+    }
+
+    // Reverts the most recent action. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.history.in_transaction() {
+            return false;
+        }
+        match self.history.pop_undo() {
+            Some(transaction) => {
+                self.reverse(&transaction);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Re-applies the most recently undone action. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        if self.history.in_transaction() {
+            return false;
+        }
+        match self.history.pop_redo() {
+            Some(transaction) => {
+                self.replay(&transaction);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    // Groups every edit until the matching end_action into a single undo step, for user actions made of several calls
+    pub fn begin_action(&mut self) {
+        self.history.begin();
+    }
+
+    pub fn end_action(&mut self) {
+        self.history.end();
+    }
+
+    // Starts a batch: add_line and add_rect only queue their lines until commit_batch, which resolves all intersections
+    // at once. Much faster than resolving after every line when adding many lines, e.g. a whole polygon.
+    pub fn begin_batch(&mut self) {
+        if self.batch.is_none() {
+            self.history.begin();
+            self.batch = Some(Batch::default());
+        }
+    }
+
+    // Adds every queued line, splitting and merging them against the drawing and each other in a single pass. The batch is
+    // all or nothing: if any edit in it failed, the whole batch is rolled back and the first error is returned.
+    pub fn commit_batch(&mut self) -> Result<(), ShapeError> {
+        let batch = match self.batch.take() {
+            Some(batch) => batch,
+            None => return Ok(()),
+        };
+        if let Some(error) = batch.error {
+            let edits = self.history.abort();
+            self.reverse(&edits);
+            return Err(error);
+        }
+        if !batch.pending.is_empty() {
+            self.insert_resolved_lines(batch.pending);
+        }
+        self.history.end();
+        Ok(())
+    }
+
+    // Throws away the batch, reverting anything done since begin_batch
+    pub fn cancel_batch(&mut self) {
+        if self.batch.take().is_some() {
+            let edits = self.history.abort();
+            self.reverse(&edits);
+        }
+    }
+}
+
+// Native (non-Wasm) access to the shape
+impl ShapeBuilder {
+    // The stored lines, each as a pair of endpoints
+    pub fn lines(&self) -> &[Vec<(f64, f64)>] {
+        &self.lines
+    }
+
+    // Centroid of the area enclosed by the lines. Loops drawn inside another loop (without touching it) are holes.
+    pub fn centroid(&self) -> Result<(f64, f64), ShapeError> {
+        Ok(self.analysis()?.section.centroid)
+    }
+
+    // Net area enclosed by the lines, with holes subtracted
+    pub fn area(&self) -> Result<f64, ShapeError> {
+        Ok(self.analysis()?.section.area)
+    }
+
+    // Every closed loop in the drawing, solid or hole. Shared with the cache, so calling this repeatedly is cheap.
+    pub fn loops(&self) -> Result<Rc<[Loop]>, ShapeError> {
+        Ok(self.analysis()?.loops.clone())
+    }
+
+    // Area, centroid, second moments of area and principal axes of the enclosed area
+    pub fn section_properties(&self) -> Result<SectionProperties, ShapeError> {
+        Ok(self.analysis()?.section)
+    }
+
+    // Named parts, in the order they were added
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    // Native access to the buffers behind vertex_buffer and edge_buffer
+    pub fn packed_vertices(&mut self) -> &[f64] {
+        self.packed.update(&self.lines, &self.vertices, self.version);
+        &self.packed.coordinates
+    }
+
+    pub fn packed_edges(&mut self) -> &[u32] {
+        self.packed.update(&self.lines, &self.vertices, self.version);
+        &self.packed.edges
+    }
+
+    // Every distinct line endpoint. A vertex's index here is its vertex ID (see vertices.rs for how IDs are kept).
+    pub fn vertices(&self) -> Vec<(f64, f64)> {
+        self.vertices.points().to_vec()
+    }
+
+    // Indices of the lines whose bounding box overlaps the given box, in ascending order
+    pub fn lines_in_box(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Vec<usize> {
+        let query = ((min_x, min_y), (max_x, max_y));
+        self.grid.near_box(query.0, query.1).into_iter()
+            .filter(|&index| arrangement::boxes_touch((self.lines[index][0], self.lines[index][1]), query))
+            .collect()
+    }
+
+    // Index of the line closest to (x, y) and its distance, if any line is within radius. Ties go to the earlier line.
+    pub fn nearest_line(&self, x: f64, y: f64, radius: f64) -> Option<(usize, f64)> {
+        let mut nearest: Option<(usize, f64)> = None;
+        for index in self.grid.near_box((x - radius, y - radius), (x + radius, y + radius)) {
+            let line = &self.lines[index];
+            let (distance, _) = grid::segment_distance((x, y), line[0], line[1]);
+            if distance <= radius && nearest.is_none_or(|(_, best)| distance < best) {
+                nearest = Some((index, distance));
+            }
+        }
+        nearest
+    }
+
+    // The line endpoint closest to (x, y) and its distance, if any endpoint is within radius
+    pub fn nearest_vertex(&self, x: f64, y: f64, radius: f64) -> Option<((f64, f64), f64)> {
+        let mut nearest: Option<((f64, f64), f64)> = None;
+        for index in self.grid.near_box((x - radius, y - radius), (x + radius, y + radius)) {
+            for &point in &self.lines[index] {
+                let distance = (point.0 - x).hypot(point.1 - y);
+                if distance <= radius && nearest.is_none_or(|(_, best)| distance < best) {
+                    nearest = Some((point, distance));
+                }
+            }
+        }
+        nearest
+    }
+
+    // Vertex ID of a stored endpoint
+    fn vertex_id(&self, point: (f64, f64)) -> Option<usize> {
+        self.vertices.id(point)
+    }
+
+    // Whether the line a -> b is stored, in either direction
+    fn contains_line(&self, a: (f64, f64), b: (f64, f64)) -> bool {
+        self.grid.near_segment(a, b).into_iter().any(|index| {
+            let line = &self.lines[index];
+            (line[0] == a && line[1] == b) || (line[0] == b && line[1] == a)
+        })
+    }
+
+    // Loops, area, centroid and section properties, from the cache unless the lines changed since they were last computed
+    fn analysis(&self) -> Result<Rc<Analysis>, ShapeError> {
+        self.analysis.get(&self.lines, self.version)
+    }
+
+    // Fails an edit. Inside a batch the failure also spoils the batch, so committing it rolls everything back.
+    fn reject(&mut self, error: ShapeError) -> Result<(), ShapeError> {
+        if let Some(batch) = self.batch.as_mut() {
+            batch.fail(error.clone());
+        }
+        Err(error)
+    }
+
+    // Curve flattening tolerances must be positive numbers
+    fn check_tolerance(&mut self, tolerance: f64) -> Result<(), ShapeError> {
+        if !tolerance.is_finite() {
+            return self.reject(ShapeError::NaNInput);
+        }
+        if tolerance <= 0.0 {
+            return self.reject(ShapeError::Degenerate);
+        }
+        Ok(())
+    }
+
+    fn check_finite(&mut self, values: &[f64]) -> Result<(), ShapeError> {
+        if values.iter().all(|value| value.is_finite()) {
+            Ok(())
+        } else {
+            self.reject(ShapeError::NaNInput)
+        }
+    }
+
+    // Maps the edges with the given IDs (every edge when there are none) and resolves them against the rest. Lines still
+    // queued in an open batch aren't stored yet, so they stay where they are.
+    fn transform_edges(&mut self, edges: Vec<usize>, map: Affine) -> Result<(), ShapeError> {
+        let indices = self.selected_edges(edges)?;
+        let moved: Vec<Vec<(f64, f64)>> = indices.iter()
+            .map(|&index| self.lines[index].iter().map(|&point| map.apply(point)).collect())
+            .collect();
+        // Moving the whole drawing takes the part labels along. With only some edges moved, a label may be inside a part
+        // that is partly moved, so labels stay put.
+        let labels = if indices.len() == self.lines.len() {
+            self.parts.iter().map(|part| (part.name.clone(), part.at, map.apply(part.at))).collect()
+        } else {
+            Vec::new()
+        };
+        // Huge factors can overflow
+        if !moved.iter().flatten().chain(labels.iter().map(|(_, _, to)| to)).all(|point| point.0.is_finite() && point.1.is_finite()) {
+            return self.reject(ShapeError::NaNInput);
+        }
+        self.transaction(|shape| {
+            for &index in indices.iter().rev() {
+                shape.remove_line(index);
+            }
+            shape.insert_resolved_lines(moved);
+            for (name, from, to) in labels {
+                shape.place_part(&name, to);
+                shape.history.record(Edit::MovePart { name, from, to });
+            }
+            Ok(())
+        })
+    }
+
+    // Adds a copy of the edges with the given IDs (every edge when there are none) through each map, resolved against the
+    // drawing and each other
+    fn copy_edges(&mut self, edges: Vec<usize>, maps: Vec<Affine>) -> Result<(), ShapeError> {
+        let indices = self.selected_edges(edges)?;
+        let copies: Vec<Vec<(f64, f64)>> = maps.iter()
+            .flat_map(|map| indices.iter().map(|&index| self.lines[index].iter().map(|&point| map.apply(point)).collect()))
+            .collect();
+        // Huge spacings or counts can overflow
+        if !copies.iter().flatten().all(|point: &(f64, f64)| point.0.is_finite() && point.1.is_finite()) {
+            return self.reject(ShapeError::NaNInput);
+        }
+        self.transaction(|shape| {
+            shape.insert_resolved_lines(copies);
+            Ok(())
+        })
+    }
+
+    // The edge IDs in ascending order without repeats, or every edge when there are none. InvalidId for an unknown edge.
+    fn selected_edges(&mut self, edges: Vec<usize>) -> Result<Vec<usize>, ShapeError> {
+        let mut indices = if edges.is_empty() { (0..self.lines.len()).collect() } else { edges };
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.last().is_some_and(|&index| index >= self.lines.len()) {
+            self.reject(ShapeError::InvalidId)?;
+        }
+        Ok(indices)
+    }
+
+    // Replaces the lines with those of the operation's result (see boolean.rs). Lines still queued in an open batch aren't
+    // part of either region.
+    fn combine(&mut self, other: &ShapeBuilder, operation: Operation) -> Result<(), ShapeError> {
+        match boolean::combine(&self.lines, &other.lines, operation) {
+            Ok(lines) => self.replace_lines(lines),
+            Err(error) => self.reject(error),
+        }
+    }
+
+    // The corner at a vertex. Degenerate if any number of edges other than two meet there.
+    fn corner(&self, id: usize) -> Result<corners::Corner, ShapeError> {
+        let corner = *self.vertices.points().get(id).ok_or(ShapeError::InvalidId)?;
+        let edges = trim::incident(&self.lines, &self.grid, corner);
+        match edges[..] {
+            [first, second] => {
+                let ends = [trim::far_end(&self.lines[first], corner), trim::far_end(&self.lines[second], corner)];
+                Ok(([first, second], [corner, ends[0], ends[1]]))
+            }
+            _ => Err(ShapeError::Degenerate),
+        }
+    }
+
+    // Swaps the two edges of a corner for the outline that replaces it, resolved against the rest, as one undo step
+    fn replace_corner(&mut self, edges: [usize; 2], outline: Vec<(f64, f64)>) -> Result<(), ShapeError> {
+        self.transaction(|shape| {
+            shape.remove_line(edges[1]);
+            shape.remove_line(edges[0]);
+            shape.insert_resolved_lines(outline.windows(2).map(|pair| pair.to_vec()).collect());
+            Ok(())
+        })
+    }
+
+    // Swaps every line for the given lines, which must already be split where they meet, as one undo step
+    fn replace_lines(&mut self, lines: Vec<Vec<(f64, f64)>>) -> Result<(), ShapeError> {
+        self.transaction(|shape| {
+            while !shape.lines.is_empty() {
+                shape.remove_line(shape.lines.len() - 1);
+            }
+            for line in lines {
+                shape.insert_line(line);
+            }
+            Ok(())
+        })
+    }
+
+    // A new shape holding the segments of a saved drawing, added as one batch but with an empty undo history
+    fn opened(segments: &[[f64; 4]]) -> Result<ShapeBuilder, ShapeError> {
+        let mut shape = ShapeBuilder::new();
+        shape.add_segments(segments)?;
+        shape.history = History::default();
+        Ok(shape)
+    }
+
+    // Adds [x1, y1, x2, y2] segments as one batch, or as part of the batch already open, all or nothing
+    fn add_segments(&mut self, segments: &[[f64; 4]]) -> Result<(), ShapeError> {
+        if self.batch.is_some() {
+            return segments.iter().try_for_each(|&[x1, y1, x2, y2]| self.add_line(x1, y1, x2, y2));
+        }
+        self.begin_batch();
+        for &[x1, y1, x2, y2] in segments {
+            if let Err(error) = self.add_line(x1, y1, x2, y2) {
+                self.cancel_batch();
+                return Err(error);
+            }
+        }
+        self.commit_batch()
+    }
+
+    // Adds many lines at once, resolving intersections and collinear overlaps in one pass (see arrangement.rs). Only the
+    // stored lines the grid finds near the new lines take part. Stored lines are already cut wherever other lines meet
+    // them, so a new line merged with a stored one can only need cuts where it runs past that line, which is near it.
+    fn insert_resolved_lines(&mut self, new_lines: Vec<Vec<(f64, f64)>>) {
+        let mut nearby: Vec<usize> = new_lines.iter().flat_map(|line| self.grid.near_segment(line[0], line[1])).collect();
+        nearby.sort_unstable();
+        nearby.dedup();
+        let subset: Vec<Vec<(f64, f64)>> = nearby.iter().map(|&index| self.lines[index].clone()).collect();
+        let resolved = arrangement::resolve(&subset, new_lines);
+        for &index in resolved.removed.iter().rev() {
+            self.remove_line(nearby[index]);
+        }
+        for line in &resolved.added {
+            self.history.record(Edit::Insert { index: self.lines.len(), line: line.clone() });
+        }
+        self.place_lines(resolved.added);
+    }
+
+    // Appends a line, recording it for undo
+    fn insert_line(&mut self, line: Vec<(f64, f64)>) {
+        self.history.record(Edit::Insert { index: self.lines.len(), line: line.clone() });
+        self.place_line(self.lines.len(), line);
+    }
+
+    // Removes the line at index, recording it for undo
+    fn remove_line(&mut self, index: usize) {
+        let line = self.take_line(index);
+        self.history.record(Edit::Remove { index, line });
+    }
+
+    // Puts a line at index without recording it, keeping the grid, vertices and version in step. Only insert_line, reverse
+    // and replay use this. The line already at index moves to the end, so this exactly reverses take_line.
+    fn place_line(&mut self, index: usize, line: Vec<(f64, f64)>) {
+        self.vertices.add(&line);
+        if index < self.lines.len() {
+            let moved = std::mem::replace(&mut self.lines[index], line);
+            self.grid.relabel(&moved, index, self.lines.len());
+            self.lines.push(moved);
+        } else {
+            self.lines.push(line);
+        }
+        self.grid.insert(&self.lines, index);
+        self.version = self.version.wrapping_add(1);
+    }
+
+    // Appends lines without recording them, like place_line but listing them in the grid in one go
+    fn place_lines(&mut self, lines: Vec<Vec<(f64, f64)>>) {
+        let first = self.lines.len();
+        for line in lines {
+            self.vertices.add(&line);
+            self.lines.push(line);
+        }
+        self.grid.extend(&self.lines, first);
+        self.version = self.version.wrapping_add(1);
+    }
+
+    // Takes out the line at index without recording it, keeping the grid, vertices and version in step. The last line
+    // takes its place, so removing several lines must go from the highest index down.
+    fn take_line(&mut self, index: usize) -> Vec<(f64, f64)> {
+        let line = self.lines.swap_remove(index);
+        self.grid.remove(index, &line);
+        if let Some(moved) = self.lines.get(index) {
+            self.grid.relabel(moved, self.lines.len(), index);
+        }
+        self.vertices.remove(&line);
+        self.version = self.version.wrapping_add(1);
+        line
+    }
+
+    // Runs an edit as one undo step. If it fails, everything it changed is reverted so the shape is left as it was.
+    fn transaction<T>(&mut self, action: impl FnOnce(&mut Self) -> Result<T, ShapeError>) -> Result<T, ShapeError> {
+        self.history.begin();
+        let result = action(self);
+        if result.is_ok() {
+            self.history.end();
+        } else {
+            let edits = self.history.abort();
+            self.reverse(&edits);
+        }
+        result
+    }
+
+    // Undoes edits, most recent first
+    fn reverse(&mut self, edits: &[Edit]) {
+        for edit in edits.iter().rev() {
+            match edit {
+                Edit::Insert { index, .. } => {
+                    self.take_line(*index);
+                }
+                Edit::Remove { index, line } => self.place_line(*index, line.clone()),
+                Edit::MovePart { name, from, .. } => self.place_part(name, *from),
+            }
+        }
+    }
+
+    // Applies edits again, in their original order
+    fn replay(&mut self, edits: &[Edit]) {
+        for edit in edits {
+            match edit {
+                Edit::Insert { index, line } => self.place_line(*index, line.clone()),
+                Edit::Remove { index, .. } => {
+                    self.take_line(*index);
+                }
+                Edit::MovePart { name, to, .. } => self.place_part(name, *to),
+            }
+        }
+    }
+
+    // Moves the label of the named part, if there still is one. Parts can be removed outside the undo history.
+    fn place_part(&mut self, name: &str, at: (f64, f64)) {
+        if let Some(part) = self.parts.iter_mut().find(|part| part.name == name) {
+            part.at = at;
+        }
     }
 }
 
 //finds if two lines intersect. If they do, split the two crossing line segments into 4 segments, each with an endpoint at the intersection.
+#[allow(clippy::too_many_arguments)]
 pub fn get_intersection(a1x: f64, a1y: f64, a2x: f64, a2y: f64, b1x: f64, b1y: f64, b2x: f64, b2y: f64, cx: &mut f64, cy: &mut f64) {
-    let alpha_num: f64 = ((b2x - b1x)*(b1y - a1y)) - ((b2y - b1y)*(b1x - a1x));
-    let beta_num: f64 = ((a2x - a1x) * (b1y - a1y)) - ((a2y - a1y) * (b1x - a1x));
-    let denom: f64 = ((b2x - b1x) * (a2y - a1y)) - ((b2y - b1y) * (a2x - a1x));
-    if denom == 0.0 && alpha_num != 0.0 {
-        //parallel but not intersecting
-        return;
-    }
-    let alpha: f64 = alpha_num / denom;
-    let beta: f64 = beta_num / denom;
-    log_f64(alpha_num);
-    log_f64(beta_num);
-    log_f64(denom);
-    if alpha_num == 0.0 && denom == 0.0 {
+    match arrangement::meet(((a1x, a1y), (a2x, a2y)), ((b1x, b1y), (b2x, b2y))) {
         //collinear/overlapping lines
-        *cx = f64::NEG_INFINITY;
-        *cy = f64::NEG_INFINITY;
-        return;
-    }
-    else if 0.0 <= alpha && alpha <= 1.0 && 0.0 <= beta && beta <= 1.0 {
-        //non-parallel and intersecting:
-        *cx = a1x + alpha * (a2x - a1x);
-        *cy = a1y + alpha * (a2y - a1y);
-        return;
-    }
-    else {
-        //not parallel and not intersecting
-        return;
+        Meeting::Collinear => {
+            *cx = f64::NEG_INFINITY;
+            *cy = f64::NEG_INFINITY;
+        }
+        //non-parallel and intersecting
+        Meeting::At((x, y)) => {
+            *cx = x;
+            *cy = y;
+        }
+        //parallel, or not parallel and not intersecting
+        Meeting::Apart => {}
     }
 }
 
+// Union of two collinear segments, treated as 1D intervals along the direction of a1 -> a2. Returns the endpoints of the
+// combined segment (keeping the direction of a) when the intervals overlap, touch or contain one another, or None when
+// there is a gap between them.
+fn collinear_union(a1: (f64, f64), a2: (f64, f64), b1: (f64, f64), b2: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+    let dx = a2.0 - a1.0;
+    let dy = a2.1 - a1.1;
+    let len_sq = dx * dx + dy * dy;
+
+    // Position of a point along a, where a1 is at 0 and a2 is at 1
+    let along = |p: (f64, f64)| ((p.0 - a1.0) * dx + (p.1 - a1.1) * dy) / len_sq;
 
+    let (t1, t2) = (along(b1), along(b2));
+    let ((lo, lo_point), (hi, hi_point)) = if t1 <= t2 { ((t1, b1), (t2, b2)) } else { ((t2, b2), (t1, b1)) };
+    if hi < 0.0 || lo > 1.0 {
+        return None;
+    }
 
+    let start = if lo < 0.0 { lo_point } else { a1 };
+    let end = if hi > 1.0 { hi_point } else { a2 };
+    Some((start, end))
+}
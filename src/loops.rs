@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use crate::arrangement::{touching_pairs, Segment};
+use crate::error::ShapeError;
+
+// A closed loop around one bounded face of the drawing
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loop {
+    // Corners of the loop, walked with the face on the left (counter-clockwise with the y axis pointing up)
+    pub points: Vec<(f64, f64)>,
+    // Area enclosed by the loop, always positive
+    pub area: f64,
+    // Number of loops enclosing this one, itself included. Separate loops drawn inside a shape cut holes into it, so odd
+    // depths are solid and even depths are holes.
+    pub depth: usize,
+}
+
+impl Loop {
+    // Whether the loop cuts a hole rather than adding material
+    pub fn is_hole(&self) -> bool {
+        self.depth.is_multiple_of(2)
+    }
+
+    // Centroid of the area enclosed by the loop on its own
+    pub fn centroid(&self) -> (f64, f64) {
+        let (mut sum_x, mut sum_y) = (0.0, 0.0);
+        for (i, &(x0, y0)) in self.points.iter().enumerate() {
+            let (x1, y1) = self.points[(i + 1) % self.points.len()];
+            let cross = x0 * y1 - x1 * y0;
+            sum_x += (x0 + x1) * cross;
+            sum_y += (y0 + y1) * cross;
+        }
+        (sum_x / (6.0 * self.area), sum_y / (6.0 * self.area))
+    }
+}
+
+// Checks that the stored lines can be turned into loops: every coordinate is finite and no two lines cross away from
+// their endpoints (add_line always splits lines where they meet, so this only fails for lines that bypassed it).
+pub(crate) fn check_lines(lines: &[Vec<(f64, f64)>]) -> Result<(), ShapeError> {
+    if lines.iter().flatten().any(|point| !point.0.is_finite() || !point.1.is_finite()) {
+        return Err(ShapeError::NaNInput);
+    }
+    // Lines that cross meet at a point, so only the pairs the sweep finds meeting need checking
+    let segments: Vec<Segment> = lines.iter().map(|line| (line[0], line[1])).collect();
+    for (i, j, _) in touching_pairs(&segments, 0) {
+        let (a, b) = (segments[i], segments[j]);
+        if segments_cross(a.0, a.1, b.0, b.1) {
+            return Err(ShapeError::SelfIntersecting);
+        }
+    }
+    Ok(())
+}
+
+// Traces every bounded face of the drawing. Each line is walked once in each direction, always turning as far left as
+// possible at a vertex, which goes around every face with the face on the left. Faces come out with positive area, while
+// the outer boundary of each connected group of lines and dangling lines come out with zero or negative area and are dropped.
+pub(crate) fn find_loops(lines: &[Vec<(f64, f64)>]) -> Vec<Loop> {
+    // Number the distinct vertices
+    let mut vertex_ids: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut vertices: Vec<(f64, f64)> = Vec::new();
+    let mut vertex_id = |point: (f64, f64)| {
+        *vertex_ids.entry(point_key(point)).or_insert_with(|| {
+            vertices.push(point);
+            vertices.len() - 1
+        })
+    };
+
+    // Half edges 2i and 2i + 1 run along the same line in opposite directions
+    let mut half_edges: Vec<(usize, usize)> = Vec::new();
+    for line in lines {
+        if line.len() < 2 {
+            continue;
+        }
+        let (from, to) = (vertex_id(line[0]), vertex_id(line[1]));
+        if from != to {
+            half_edges.push((from, to));
+            half_edges.push((to, from));
+        }
+    }
+
+    // Outgoing half edges of every vertex, sorted counter-clockwise by angle
+    let angle = |h: usize| {
+        let (from, to) = half_edges[h];
+        (vertices[to].1 - vertices[from].1).atan2(vertices[to].0 - vertices[from].0)
+    };
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for (h, &(from, _)) in half_edges.iter().enumerate() {
+        outgoing[from].push(h);
+    }
+    let mut position = vec![0; half_edges.len()];
+    for list in outgoing.iter_mut() {
+        list.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+        for (i, &h) in list.iter().enumerate() {
+            position[h] = i;
+        }
+    }
+
+    // Group vertices into connected components
+    let mut parent: Vec<usize> = (0..vertices.len()).collect();
+    for &(from, to) in half_edges.iter().step_by(2) {
+        let (a, b) = (find_root(&mut parent, from), find_root(&mut parent, to));
+        parent[a] = b;
+    }
+
+    // Walk the faces. Arriving at a vertex, leave along the half edge just clockwise of the way back.
+    let mut visited = vec![false; half_edges.len()];
+    let mut faces: Vec<Loop> = Vec::new();
+    let mut components: Vec<usize> = Vec::new();
+    for start in 0..half_edges.len() {
+        if visited[start] {
+            continue;
+        }
+        let mut points = Vec::new();
+        let mut h = start;
+        while !visited[h] {
+            visited[h] = true;
+            let (from, to) = half_edges[h];
+            points.push(vertices[from]);
+            let around = &outgoing[to];
+            h = around[(position[h ^ 1] + around.len() - 1) % around.len()];
+        }
+        let area = signed_area(&points);
+        if area > 0.0 {
+            faces.push(Loop { points, area, depth: 1 });
+            components.push(find_root(&mut parent, half_edges[start].0));
+        }
+    }
+
+    // A face is nested inside every face of another component that contains it. Components never touch, so testing a
+    // single corner is enough.
+    let depths: Vec<usize> = faces.iter().zip(&components)
+        .map(|(face, component)| {
+            1 + faces.iter().zip(&components)
+                .filter(|(other, other_component)| *other_component != component && point_in_polygon(face.points[0], &other.points))
+                .count()
+        })
+        .collect();
+    for (face, depth) in faces.iter_mut().zip(depths) {
+        face.depth = depth;
+    }
+    faces
+}
+
+// Area and centroid of the solid region: loops at odd depths add material and loops at even depths cut it away
+pub(crate) fn area_centroid(loops: &[Loop]) -> (f64, (f64, f64)) {
+    let (mut area, mut moment_x, mut moment_y) = (0.0, 0.0, 0.0);
+    for found in loops {
+        let sign = if found.is_hole() { -1.0 } else { 1.0 };
+        let (cx, cy) = found.centroid();
+        area += sign * found.area;
+        moment_x += sign * found.area * cx;
+        moment_y += sign * found.area * cy;
+    }
+    (area, (moment_x / area, moment_y / area))
+}
+
+// Key for looking up a vertex by its exact coordinates (with -0.0 folded into 0.0)
+pub(crate) fn point_key(point: (f64, f64)) -> (u64, u64) {
+    ((point.0 + 0.0).to_bits(), (point.1 + 0.0).to_bits())
+}
+
+fn find_root(parent: &mut [usize], mut v: usize) -> usize {
+    while parent[v] != v {
+        parent[v] = parent[parent[v]];
+        v = parent[v];
+    }
+    v
+}
+
+// Shoelace formula, positive for counter-clockwise points
+pub(crate) fn signed_area(points: &[(f64, f64)]) -> f64 {
+    let mut sum = 0.0;
+    for (i, &(x0, y0)) in points.iter().enumerate() {
+        let (x1, y1) = points[(i + 1) % points.len()];
+        sum += x0 * y1 - x1 * y0;
+    }
+    sum / 2.0
+}
+
+// Even-odd ray cast to the right of the point
+pub(crate) fn point_in_polygon(point: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    for (i, &(x0, y0)) in polygon.iter().enumerate() {
+        let (x1, y1) = polygon[(i + 1) % polygon.len()];
+        if (y0 > point.1) != (y1 > point.1) && point.0 < x0 + (point.1 - y0) * (x1 - x0) / (y1 - y0) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// Twice the signed area of the triangle a, b, c: positive when c is left of a -> b
+fn orientation(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+// Whether two segments cross at a point strictly inside both of them
+pub(crate) fn segments_cross(a1: (f64, f64), a2: (f64, f64), b1: (f64, f64), b2: (f64, f64)) -> bool {
+    if a1.0.max(a2.0) < b1.0.min(b2.0) || b1.0.max(b2.0) < a1.0.min(a2.0)
+        || a1.1.max(a2.1) < b1.1.min(b2.1) || b1.1.max(b2.1) < a1.1.min(a2.1) {
+        return false;
+    }
+    let (o1, o2) = (orientation(a1, a2, b1), orientation(a1, a2, b2));
+    let (o3, o4) = (orientation(b1, b2, a1), orientation(b1, b2, a2));
+    o1 * o2 < 0.0 && o3 * o4 < 0.0
+}
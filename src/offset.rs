@@ -0,0 +1,93 @@
+// Grows or shrinks the solid region by a distance, for ShapeBuilder::offset.
+//
+// Growing adds a strip of the given width along the outside of every boundary edge, and fills the gap the strips leave at
+// each convex corner with a join. Shrinking does the same along the inside, at the corners that are convex seen from the
+// inside, and cuts the strips and joins away. The strips and joins are combined with the region by boolean.rs.
+use wasm_bindgen::prelude::*;
+use crate::boolean::{self, Operation};
+use crate::curves;
+use crate::error::ShapeError;
+use crate::geo;
+use crate::loops::Loop;
+
+type Point = (f64, f64);
+
+// How the offset outline goes round a corner
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Join {
+    // The offset edges are extended until they meet, or cut off square (bevelled) if that's more than MITER_LIMIT times
+    // the distance from the corner
+    Miter,
+    // An arc around the corner, within tolerance of a true circle
+    Round,
+    // Cut off square, at the offset distance from the corner
+    Square,
+}
+
+// Longest miter, as a multiple of the offset distance, as in SVG's default stroke-miterlimit
+const MITER_LIMIT: f64 = 4.0;
+
+// Lines of the region grown by distance, or shrunk when distance is negative. found are the drawing's loops.
+pub(crate) fn offset(lines: &[Vec<Point>], found: &[Loop], distance: f64, join: Join, tolerance: f64) -> Result<Vec<Vec<Point>>, ShapeError> {
+    let width = distance.abs();
+    let mut pieces: Vec<Vec<Point>> = Vec::new();
+    for ring in geo::outline(found).into_iter().flatten() {
+        // Rings have the material on their left. Walk them with the side being offset into on the right.
+        let ring: Vec<Point> = if distance > 0.0 { ring } else { ring.into_iter().rev().collect() };
+        let count = ring.len();
+        // Direction and right-hand normal of the edge from each point to the next
+        let directions: Vec<(Point, Point)> = (0..count).map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % count]);
+            let length = (b.0 - a.0).hypot(b.1 - a.1);
+            let along = ((b.0 - a.0) / length, (b.1 - a.1) / length);
+            (along, (along.1, -along.0))
+        }).collect();
+        let shifted = |point: Point, normal: Point| (point.0 + width * normal.0, point.1 + width * normal.1);
+
+        for i in 0..count {
+            let (a, b) = (ring[i], ring[(i + 1) % count]);
+            let normal = directions[i].1;
+            pieces.push(vec![a, shifted(a, normal), shifted(b, normal), b]);
+
+            // The strips leave a gap at corners turning left, away from the side being offset into
+            let (t1, n1) = directions[(i + count - 1) % count];
+            let (t2, n2) = directions[i];
+            let (cross, dot) = (t1.0 * t2.1 - t1.1 * t2.0, t1.0 * t2.0 + t1.1 * t2.1);
+            if cross <= 0.0 {
+                continue;
+            }
+            let (p1, p2) = (shifted(a, n1), shifted(a, n2));
+            let mut corner = vec![a, p1];
+            match join {
+                Join::Miter => {
+                    // The offset edges meet 1 / cos(half the turn) times the distance from the corner
+                    let half_turn_cos = ((1.0 + dot) / 2.0).sqrt();
+                    if half_turn_cos * MITER_LIMIT >= 1.0 {
+                        let scale = width / (1.0 + n1.0 * n2.0 + n1.1 * n2.1);
+                        corner.push((a.0 + scale * (n1.0 + n2.0), a.1 + scale * (n1.1 + n2.1)));
+                    }
+                }
+                Join::Round => {
+                    let start = n1.1.atan2(n1.0);
+                    let mut arc = curves::ellipse_points(a, width, width, 0.0, start, cross.atan2(dot), tolerance);
+                    arc.pop();
+                    corner.extend(arc);
+                }
+                Join::Square => {
+                    // Cut across the bisector, at the offset distance from the corner
+                    let bisector = (n1.0 + n2.0, n1.1 + n2.1);
+                    let length = bisector.0.hypot(bisector.1);
+                    let u = (bisector.0 / length, bisector.1 / length);
+                    let reach = width * (1.0 - (n1.0 * u.0 + n1.1 * u.1)) / (t1.0 * u.0 + t1.1 * u.1);
+                    corner.push((p1.0 + reach * t1.0, p1.1 + reach * t1.1));
+                    corner.push((p2.0 - reach * t2.0, p2.1 - reach * t2.1));
+                }
+            }
+            corner.push(p2);
+            pieces.push(corner);
+        }
+    }
+    let operation = if distance > 0.0 { Operation::Union } else { Operation::Difference };
+    boolean::combine_polygons(lines, &pieces, operation)
+}
@@ -0,0 +1,26 @@
+// Result of ShapeBuilder::pick: the piece of geometry under the cursor.
+//
+// Edge IDs are indices into ShapeBuilder::lines. Vertex IDs are indices into ShapeBuilder::vertices, which lists every
+// distinct line endpoint. Both are only valid until the shape is next edited: removing a line or vertex moves the last
+// one into its place (see vertices.rs), so an old ID can name something else afterwards.
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickKind {
+    Vertex,
+    Edge,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pick {
+    pub kind: PickKind,
+    // Vertex or edge ID, depending on kind
+    pub id: usize,
+    // Distance from the picked point to the geometry
+    pub distance: f64,
+    // Closest point on the geometry: the vertex itself, or the picked point projected onto the edge
+    pub x: f64,
+    pub y: f64,
+}
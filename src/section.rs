@@ -0,0 +1,92 @@
+// Section properties of the enclosed area: the figures a structural engineer needs besides the centroid.
+//
+// Second moments of area are taken about axes through the centroid, parallel to x and y. Like the area and centroid, each
+// loop adds its contribution and holes subtract theirs.
+use std::fmt;
+use serde::Serialize;
+use crate::loops::Loop;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct SectionProperties {
+    pub area: f64,
+    pub centroid: (f64, f64),
+    // Second moment of area about the horizontal axis through the centroid, the integral of y² dA
+    pub ixx: f64,
+    // Second moment of area about the vertical axis through the centroid, the integral of x² dA
+    pub iyy: f64,
+    // Product of area about the centroid, the integral of xy dA
+    pub ixy: f64,
+    // Principal second moments: the largest and smallest second moment about any axis through the centroid
+    pub i_major: f64,
+    pub i_minor: f64,
+    // Angle of the major principal axis from the x axis, in radians, above -π/2 and up to π/2
+    pub principal_angle: f64,
+}
+
+impl SectionProperties {
+    // Computes the properties of loops whose net area and centroid are already known
+    pub(crate) fn from_loops(loops: &[Loop], area: f64, centroid: (f64, f64)) -> SectionProperties {
+        // Second moments about the centroid, from Green's theorem over each loop's edges (points are counter-clockwise).
+        // Points are measured from the centroid first: working about the origin and moving the result with the parallel
+        // axis theorem loses most of the digits for a section drawn far from the origin.
+        let (cx, cy) = centroid;
+        let (mut ixx, mut iyy, mut ixy) = (0.0, 0.0, 0.0);
+        for found in loops {
+            let sign = if found.is_hole() { -1.0 } else { 1.0 };
+            let points = &found.points;
+            for (i, &(px0, py0)) in points.iter().enumerate() {
+                let (px1, py1) = points[(i + 1) % points.len()];
+                let (x0, y0, x1, y1) = (px0 - cx, py0 - cy, px1 - cx, py1 - cy);
+                let cross = x0 * y1 - x1 * y0;
+                ixx += sign * cross * (y0 * y0 + y0 * y1 + y1 * y1) / 12.0;
+                iyy += sign * cross * (x0 * x0 + x0 * x1 + x1 * x1) / 12.0;
+                ixy += sign * cross * (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0) / 24.0;
+            }
+        }
+
+        // Mohr's circle
+        let average = (ixx + iyy) / 2.0;
+        let radius = ((ixx - iyy) / 2.0).hypot(ixy);
+        let mut principal_angle = 0.5 * (-2.0 * ixy).atan2(ixx - iyy);
+        if principal_angle <= -std::f64::consts::FRAC_PI_2 {
+            principal_angle += std::f64::consts::PI; // Same axis, pointing the other way
+        }
+        SectionProperties {
+            area,
+            centroid,
+            ixx,
+            iyy,
+            ixy,
+            i_major: average + radius,
+            i_minor: average - radius,
+            principal_angle,
+        }
+    }
+
+    // Polar second moment of area about the centroid
+    pub fn polar(&self) -> f64 {
+        self.ixx + self.iyy
+    }
+
+    // Radii of gyration about the centroidal x and y axes
+    pub fn radii_of_gyration(&self) -> (f64, f64) {
+        ((self.ixx / self.area).sqrt(), (self.iyy / self.area).sqrt())
+    }
+}
+
+// Readable multi-line report of every property
+impl fmt::Display for SectionProperties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (rx, ry) = self.radii_of_gyration();
+        writeln!(f, "Area:              {}", self.area)?;
+        writeln!(f, "Centroid:          ({}, {})", self.centroid.0, self.centroid.1)?;
+        writeln!(f, "Ixx:               {}", self.ixx)?;
+        writeln!(f, "Iyy:               {}", self.iyy)?;
+        writeln!(f, "Ixy:               {}", self.ixy)?;
+        writeln!(f, "J (polar):         {}", self.polar())?;
+        writeln!(f, "I major:           {}", self.i_major)?;
+        writeln!(f, "I minor:           {}", self.i_minor)?;
+        writeln!(f, "Principal angle:   {} deg", self.principal_angle.to_degrees())?;
+        write!(f, "Radii of gyration: ({}, {})", rx, ry)
+    }
+}
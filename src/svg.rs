@@ -0,0 +1,586 @@
+// SVG import and export: turns the outlines in an SVG file into line segments for ShapeBuilder::import_svg, and draws a
+// drawing as an annotated figure for ShapeBuilder::to_svg.
+//
+// Import:
+//
+// Reads <path> (M, L, H, V, Z, C, S, Q, T and A commands, absolute and relative), <polygon>, <polyline>, <line>, <rect>
+// (including rounded corners), <circle> and <ellipse>, applying the transform of each element and of every group around
+// it. Curves, arcs, circles and ellipses are replaced by straight segments that stay within a given tolerance of the curve.
+// Anything inside <defs>, <clipPath>, <mask>, <marker>, <pattern> or <symbol> isn't drawn by itself and is skipped, as are
+// the "regions" and "annotations" added by export.
+//
+// Coordinates are SVG user units, unchanged, so y points down as it does in the file. Only plain numbers and px lengths
+// are accepted, since other units and percentages depend on how the file is displayed.
+//
+// Export writes the segments, fills the enclosed regions with holes left empty, and marks the centroid, the principal
+// axes and the overall width and height with labelled annotations. Coordinates are written unchanged, so exporting and
+// importing again gives back the same segments.
+use std::f64::consts::PI;
+use std::fmt::Write;
+use roxmltree::{Document, Node};
+use crate::analysis::Analysis;
+use crate::curves::{bezier, ellipse_points};
+use crate::error::ShapeError;
+use crate::format;
+
+type Point = (f64, f64);
+
+// 2D affine transform [a, b, c, d, e, f], mapping (x, y) to (a x + c y + e, b x + d y + f) as in SVG's matrix()
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Transform([f64; 6]);
+
+impl Transform {
+    const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    fn apply(&self, (x, y): Point) -> Point {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+
+    // self, then other
+    fn then(&self, other: &Transform) -> Transform {
+        let [a1, b1, c1, d1, e1, f1] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = other.0;
+        Transform([
+            a2 * a1 + c2 * b1,
+            b2 * a1 + d2 * b1,
+            a2 * c1 + c2 * d1,
+            b2 * c1 + d2 * d1,
+            a2 * e1 + c2 * f1 + e2,
+            b2 * e1 + d2 * f1 + f2,
+        ])
+    }
+
+    // Largest factor the transform stretches any length by
+    fn max_scale(&self) -> f64 {
+        let [a, b, c, d, _, _] = self.0;
+        let sum = a * a + b * b + c * c + d * d;
+        let det = a * d - b * c;
+        ((sum + (sum * sum - 4.0 * det * det).max(0.0).sqrt()) / 2.0).sqrt()
+    }
+}
+
+// Every segment drawn by the SVG, as [x1, y1, x2, y2]. tolerance is the furthest a segment may stray from a curve it
+// replaces, in the same units as the result.
+pub(crate) fn parse(svg: &str, tolerance: f64) -> Result<Vec<[f64; 4]>, ShapeError> {
+    let document = Document::parse(svg).map_err(|error| ShapeError::Parse { line: Some(error.pos().row as usize), message: error.to_string() })?;
+    let mut segments = Vec::new();
+    visit(&document, document.root_element(), Transform::IDENTITY, tolerance, &mut segments)?;
+    Ok(segments)
+}
+
+fn visit(document: &Document, node: Node, parent: Transform, tolerance: f64, segments: &mut Vec<[f64; 4]>) -> Result<(), ShapeError> {
+    let name = node.tag_name().name();
+    if matches!(name, "defs" | "clipPath" | "mask" | "marker" | "pattern" | "symbol") {
+        return Ok(());
+    }
+    // The filled regions and annotations of a figure written by to_svg only repeat or describe its segments
+    if node.attribute("class").is_some_and(|class| class.split_whitespace().any(|c| c == "regions" || c == "annotations")) {
+        return Ok(());
+    }
+    let fail = |message: String| ShapeError::Parse { line: Some(document.text_pos_at(node.range().start).row as usize), message };
+
+    let transform = match node.attribute("transform") {
+        Some(text) => parse_transform(text).map_err(fail)?.then(&parent),
+        None => parent,
+    };
+    // Flatten in the element's own coordinates, tightening the tolerance by however much the transform stretches things
+    let local_tolerance = tolerance / transform.max_scale().max(f64::MIN_POSITIVE);
+    let number = |attribute: &str| length(node.attribute(attribute).unwrap_or("0")).map_err(fail);
+
+    let polylines: Vec<Vec<Point>> = match name {
+        "path" => path(node.attribute("d").unwrap_or(""), local_tolerance).map_err(fail)?,
+        "polygon" | "polyline" => {
+            let values = numbers(node.attribute("points").unwrap_or("")).map_err(fail)?;
+            let mut points: Vec<Point> = values.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect();
+            if name == "polygon" && !points.is_empty() {
+                points.push(points[0]);
+            }
+            vec![points]
+        }
+        "line" => vec![vec![(number("x1")?, number("y1")?), (number("x2")?, number("y2")?)]],
+        "rect" => {
+            let (x, y, width, height) = (number("x")?, number("y")?, number("width")?, number("height")?);
+            // A missing rx or ry takes the other's value, and neither can exceed half the side
+            let (rx, ry) = match (node.attribute("rx"), node.attribute("ry")) {
+                (None, None) => (0.0, 0.0),
+                (Some(_), None) => (number("rx")?, number("rx")?),
+                (None, Some(_)) => (number("ry")?, number("ry")?),
+                (Some(_), Some(_)) => (number("rx")?, number("ry")?),
+            };
+            vec![rect(x, y, width, height, rx.min(width / 2.0), ry.min(height / 2.0), local_tolerance)]
+        }
+        "circle" => {
+            let r = number("r")?;
+            vec![ellipse((number("cx")?, number("cy")?), r, r, local_tolerance)]
+        }
+        "ellipse" => vec![ellipse((number("cx")?, number("cy")?), number("rx")?, number("ry")?, local_tolerance)],
+        _ => Vec::new(),
+    };
+    for points in polylines {
+        let points: Vec<Point> = points.into_iter().map(|point| transform.apply(point)).collect();
+        for pair in points.windows(2) {
+            if pair[0] != pair[1] {
+                segments.push([pair[0].0, pair[0].1, pair[1].0, pair[1].1]);
+            }
+        }
+    }
+
+    for child in node.children().filter(Node::is_element) {
+        visit(document, child, transform, tolerance, segments)?;
+    }
+    Ok(())
+}
+
+// A number with an optional px unit
+fn length(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    let number = text.strip_suffix("px").unwrap_or(text).trim_end();
+    number.parse::<f64>().map_err(|_| format!("unsupported length \"{}\"", text))
+}
+
+// Reads numbers and arc flags out of path data, point lists and transform arguments, where numbers can be separated by
+// whitespace, a comma, or nothing at all when the next one starts with a sign or a second decimal point ("1-2.5.5")
+struct Scanner<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Scanner<'a> {
+        Scanner { text: text.as_bytes(), position: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.position < self.text.len() && (self.text[self.position].is_ascii_whitespace() || self.text[self.position] == b',') {
+            self.position += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.position >= self.text.len()
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.text.get(self.position).copied()
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        self.skip_separators();
+        let start = self.position;
+        let mut end = start;
+        if matches!(self.text.get(end), Some(b'-' | b'+')) {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        while let Some(&c) = self.text.get(end) {
+            if c.is_ascii_digit() {
+                end += 1;
+            } else if c == b'.' && !seen_dot {
+                seen_dot = true;
+                end += 1;
+            } else {
+                break;
+            }
+        }
+        // Exponent, as long as digits follow
+        if matches!(self.text.get(end), Some(b'e' | b'E')) {
+            let mut exponent_end = end + 1;
+            if matches!(self.text.get(exponent_end), Some(b'-' | b'+')) {
+                exponent_end += 1;
+            }
+            if self.text.get(exponent_end).is_some_and(u8::is_ascii_digit) {
+                while self.text.get(exponent_end).is_some_and(u8::is_ascii_digit) {
+                    exponent_end += 1;
+                }
+                end = exponent_end;
+            }
+        }
+        let token = std::str::from_utf8(&self.text[start..end]).unwrap_or("");
+        match token.parse::<f64>() {
+            Ok(value) => {
+                self.position = end;
+                Ok(value)
+            }
+            Err(_) => Err(format!("expected a number at \"{}\"", self.rest())),
+        }
+    }
+
+    // An arc flag, a single 0 or 1 that needs no separator after it
+    fn flag(&mut self) -> Result<bool, String> {
+        match self.peek() {
+            Some(b'0') => {
+                self.position += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.position += 1;
+                Ok(true)
+            }
+            _ => Err(format!("expected an arc flag (0 or 1) at \"{}\"", self.rest())),
+        }
+    }
+
+    fn rest(&self) -> String {
+        String::from_utf8_lossy(&self.text[self.position..]).chars().take(20).collect()
+    }
+}
+
+fn numbers(text: &str) -> Result<Vec<f64>, String> {
+    let mut scanner = Scanner::new(text);
+    let mut values = Vec::new();
+    while !scanner.at_end() {
+        values.push(scanner.number()?);
+    }
+    Ok(values)
+}
+
+// A transform attribute: a list of matrix, translate, scale, rotate, skewX and skewY, applied right to left
+fn parse_transform(text: &str) -> Result<Transform, String> {
+    let mut result = Transform::IDENTITY;
+    let mut rest = text.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    while !rest.is_empty() {
+        let open = rest.find('(').ok_or_else(|| format!("expected \"(\" in transform \"{}\"", text))?;
+        // Only a ")" after the "(" closes it
+        let close = rest[open..].find(')').map(|i| open + i).ok_or_else(|| format!("expected \")\" in transform \"{}\"", text))?;
+        let name = rest[..open].trim();
+        let values = numbers(&rest[open + 1..close])?;
+        let transform = match (name, values.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform([a, b, c, d, e, f]),
+            ("translate", &[x]) => Transform([1.0, 0.0, 0.0, 1.0, x, 0.0]),
+            ("translate", &[x, y]) => Transform([1.0, 0.0, 0.0, 1.0, x, y]),
+            ("scale", &[s]) => Transform([s, 0.0, 0.0, s, 0.0, 0.0]),
+            ("scale", &[sx, sy]) => Transform([sx, 0.0, 0.0, sy, 0.0, 0.0]),
+            ("rotate", &[angle]) => rotation(angle),
+            ("rotate", &[angle, cx, cy]) => Transform([1.0, 0.0, 0.0, 1.0, -cx, -cy])
+                .then(&rotation(angle))
+                .then(&Transform([1.0, 0.0, 0.0, 1.0, cx, cy])),
+            ("skewX", &[angle]) => Transform([1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0]),
+            ("skewY", &[angle]) => Transform([1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+            _ => return Err(format!("unsupported transform \"{}\"", rest[..=close].trim())),
+        };
+        // Written left to right, applied right to left
+        result = transform.then(&result);
+        rest = rest[close + 1..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+    Ok(result)
+}
+
+fn rotation(degrees: f64) -> Transform {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    Transform([cos, sin, -sin, cos, 0.0, 0.0])
+}
+
+// Path data, as one polyline per subpath
+fn path(data: &str, tolerance: f64) -> Result<Vec<Vec<Point>>, String> {
+    let mut scanner = Scanner::new(data);
+    let mut polylines: Vec<Vec<Point>> = Vec::new();
+    let mut current: Vec<Point> = Vec::new();
+    let mut position = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    // Last control point of the previous curve, for S and T
+    let mut last_cubic: Option<Point> = None;
+    let mut last_quadratic: Option<Point> = None;
+    let mut command = None;
+
+    while !scanner.at_end() {
+        // A command letter, or more arguments for the previous command
+        let letter = match scanner.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                scanner.position += 1;
+                c
+            }
+            Some(_) => match command {
+                // Extra pairs after a moveto are linetos
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(previous) if !matches!(previous, b'Z' | b'z') => previous,
+                _ => return Err(format!("expected a path command at \"{}\"", scanner.rest())),
+            },
+            None => break,
+        };
+        if command.is_none() && !matches!(letter, b'M' | b'm') {
+            return Err("path data must start with a moveto (M or m)".to_string());
+        }
+        let relative = letter.is_ascii_lowercase();
+        let origin = if relative { position } else { (0.0, 0.0) };
+        let point = |scanner: &mut Scanner| -> Result<Point, String> {
+            let x = scanner.number()?;
+            let y = scanner.number()?;
+            Ok((origin.0 + x, origin.1 + y))
+        };
+
+        let (mut next_cubic, mut next_quadratic) = (None, None);
+        match letter.to_ascii_uppercase() {
+            b'M' => {
+                if current.len() > 1 {
+                    polylines.push(std::mem::take(&mut current));
+                }
+                position = point(&mut scanner)?;
+                start = position;
+                current = vec![position];
+            }
+            b'L' => {
+                position = point(&mut scanner)?;
+                current.push(position);
+            }
+            b'H' => {
+                let x = scanner.number()?;
+                position = (if relative { position.0 + x } else { x }, position.1);
+                current.push(position);
+            }
+            b'V' => {
+                let y = scanner.number()?;
+                position = (position.0, if relative { position.1 + y } else { y });
+                current.push(position);
+            }
+            b'Z' => {
+                if current.len() > 1 {
+                    current.push(start);
+                    polylines.push(std::mem::take(&mut current));
+                }
+                position = start;
+                current = vec![start];
+            }
+            b'C' | b'S' => {
+                let control_1 = if letter.eq_ignore_ascii_case(&b'C') {
+                    point(&mut scanner)?
+                } else {
+                    // Reflection of the previous curve's second control point, or the current point
+                    last_cubic.map_or(position, |(x, y)| (2.0 * position.0 - x, 2.0 * position.1 - y))
+                };
+                let control_2 = point(&mut scanner)?;
+                let end = point(&mut scanner)?;
+                current.extend(bezier(&[position, control_1, control_2, end], tolerance));
+                next_cubic = Some(control_2);
+                position = end;
+            }
+            b'Q' | b'T' => {
+                let control = if letter.eq_ignore_ascii_case(&b'Q') {
+                    point(&mut scanner)?
+                } else {
+                    last_quadratic.map_or(position, |(x, y)| (2.0 * position.0 - x, 2.0 * position.1 - y))
+                };
+                let end = point(&mut scanner)?;
+                current.extend(bezier(&[position, control, end], tolerance));
+                next_quadratic = Some(control);
+                position = end;
+            }
+            b'A' => {
+                let (rx, ry) = (scanner.number()?.abs(), scanner.number()?.abs());
+                let rotation = scanner.number()?;
+                let (large_arc, sweep) = (scanner.flag()?, scanner.flag()?);
+                let end = point(&mut scanner)?;
+                current.extend(arc(position, rx, ry, rotation, large_arc, sweep, end, tolerance));
+                position = end;
+            }
+            other => return Err(format!("unsupported path command \"{}\"", other as char)),
+        }
+        last_cubic = next_cubic;
+        last_quadratic = next_quadratic;
+        command = Some(letter);
+    }
+    if current.len() > 1 {
+        polylines.push(current);
+    }
+    Ok(polylines)
+}
+
+// Points along an elliptical arc given the SVG way, by its endpoints (SVG spec, appendix B.2.4), after the start point
+#[allow(clippy::too_many_arguments)]
+fn arc(from: Point, rx: f64, ry: f64, rotation: f64, large_arc: bool, sweep: bool, to: Point, tolerance: f64) -> Vec<Point> {
+    if from == to {
+        return Vec::new();
+    }
+    if rx == 0.0 || ry == 0.0 {
+        return vec![to];
+    }
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    // Start point in the ellipse's own axes, relative to the chord's midpoint
+    let (dx, dy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+    // Radii too small to reach are scaled up until they just do
+    let reach = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    let (rx, ry) = if reach > 1.0 { (rx * reach.sqrt(), ry * reach.sqrt()) } else { (rx, ry) };
+
+    let numerator = (rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1).max(0.0);
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut factor = (numerator / denominator).sqrt();
+    if large_arc == sweep {
+        factor = -factor;
+    }
+    let (cx1, cy1) = (factor * rx * y1 / ry, -factor * ry * x1 / rx);
+    let center = (cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0, sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0);
+
+    let angle = |ux: f64, uy: f64| uy.atan2(ux);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut sweep_angle = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - start;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += 2.0 * PI;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= 2.0 * PI;
+    }
+
+    let mut points = ellipse_points(center, rx, ry, rotation.to_radians(), start, sweep_angle, tolerance);
+    // End exactly on the given point, so the next command continues from it
+    if let Some(last) = points.last_mut() {
+        *last = to;
+    }
+    points
+}
+
+// A closed ellipse, starting and ending at its rightmost point
+fn ellipse(center: Point, rx: f64, ry: f64, tolerance: f64) -> Vec<Point> {
+    if rx <= 0.0 || ry <= 0.0 {
+        return Vec::new();
+    }
+    let start = (center.0 + rx, center.1);
+    let mut points = vec![start];
+    points.extend(ellipse_points(center, rx, ry, 0.0, 0.0, 2.0 * PI, tolerance));
+    *points.last_mut().unwrap() = start;
+    points
+}
+
+// A closed rectangle, with corners rounded by quarter ellipses when rx and ry are positive
+fn rect(x: f64, y: f64, width: f64, height: f64, rx: f64, ry: f64, tolerance: f64) -> Vec<Point> {
+    if width <= 0.0 || height <= 0.0 {
+        return Vec::new();
+    }
+    if rx <= 0.0 || ry <= 0.0 {
+        return vec![(x, y), (x + width, y), (x + width, y + height), (x, y + height), (x, y)];
+    }
+    let mut points = vec![(x + rx, y)];
+    let corner = |points: &mut Vec<Point>, center: Point, start: f64| {
+        points.extend(ellipse_points(center, rx, ry, 0.0, start, PI / 2.0, tolerance));
+    };
+    points.push((x + width - rx, y));
+    corner(&mut points, (x + width - rx, y + ry), -PI / 2.0);
+    points.push((x + width, y + height - ry));
+    corner(&mut points, (x + width - rx, y + height - ry), 0.0);
+    points.push((x + rx, y + height));
+    corner(&mut points, (x + rx, y + height - ry), PI / 2.0);
+    points.push((x, y + ry));
+    corner(&mut points, (x + rx, y + ry), PI);
+    *points.last_mut().unwrap() = (x + rx, y);
+    points
+}
+
+// Colours of the exported figure
+const FILL: &str = "#cfe2f3";
+const STROKE: &str = "#000000";
+const AXIS: &str = "#cc0000";
+const DIMENSION: &str = "#555555";
+
+// Width or height of the exported figure, whichever is larger, in px
+const FIGURE_SIZE: f64 = 800.0;
+
+// The drawing as an SVG figure. analysis is left out when the lines don't enclose anything, which leaves only the segments.
+pub(crate) fn write(lines: &[Vec<Point>], analysis: Option<&Analysis>, units: &str) -> String {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in lines.iter().flatten() {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    if lines.is_empty() {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 1 1\"/>\n".to_string();
+    }
+    // Line widths, text and the margin for the annotations all scale with the drawing
+    let size = (max_x - min_x).max(max_y - min_y);
+    let stroke = size / 400.0;
+    let font = size / 30.0;
+    let margin = size / 4.0;
+    let (view_width, view_height) = (max_x - min_x + 2.0 * margin, max_y - min_y + 2.0 * margin);
+    let scale = FIGURE_SIZE / view_width.max(view_height);
+
+    let mut out = String::new();
+    // Writing to a String can't fail
+    let _ = writeln!(out, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">",
+        min_x - margin, min_y - margin, view_width, view_height, (view_width * scale).round(), (view_height * scale).round());
+
+    // Regions, with every loop in one path so the even-odd rule leaves holes (even depths) empty
+    if let Some(analysis) = analysis {
+        let mut data = String::new();
+        for found in analysis.loops.iter() {
+            for (i, &(x, y)) in found.points.iter().enumerate() {
+                let _ = write!(data, "{}{} {} ", if i == 0 { "M" } else { "L" }, x, y);
+            }
+            data.push_str("Z ");
+        }
+        let _ = writeln!(out, "  <path class=\"regions\" d=\"{}\" fill=\"{}\" fill-rule=\"evenodd\" stroke=\"none\"/>", data.trim_end(), FILL);
+    }
+
+    let _ = writeln!(out, "  <g class=\"segments\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\">", STROKE, stroke);
+    for line in lines {
+        let _ = writeln!(out, "    <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", line[0].0, line[0].1, line[1].0, line[1].1);
+    }
+    out.push_str("  </g>\n");
+
+    let _ = writeln!(out, "  <g class=\"annotations\" font-family=\"sans-serif\" font-size=\"{}\" stroke-width=\"{}\">", font, stroke);
+    let unit_suffix = if units.is_empty() { String::new() } else { format!(" {}", escape(units)) };
+
+    // Overall width below the drawing and height to its left, with a tick at each end
+    let (below, left) = (max_y + margin / 2.0, min_x - margin / 2.0);
+    let tick = margin / 8.0;
+    let _ = writeln!(out, "    <g class=\"dimensions\" stroke=\"{}\" fill=\"{}\">", DIMENSION, DIMENSION);
+    let _ = writeln!(out, "      <path d=\"M{} {} L{} {} M{} {} L{} {} M{} {} L{} {}\" fill=\"none\"/>",
+        min_x, below, max_x, below, min_x, below - tick, min_x, below + tick, max_x, below - tick, max_x, below + tick);
+    let _ = writeln!(out, "      <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" stroke=\"none\">{}{}</text>",
+        (min_x + max_x) / 2.0, below - tick, label(max_x - min_x), unit_suffix);
+    let _ = writeln!(out, "      <path d=\"M{} {} L{} {} M{} {} L{} {} M{} {} L{} {}\" fill=\"none\"/>",
+        left, min_y, left, max_y, left - tick, min_y, left + tick, min_y, left - tick, max_y, left + tick, max_y);
+    let _ = writeln!(out, "      <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" stroke=\"none\" transform=\"rotate(-90 {} {})\">{}{}</text>",
+        left - tick, (min_y + max_y) / 2.0, left - tick, (min_y + max_y) / 2.0, label(max_y - min_y), unit_suffix);
+    out.push_str("    </g>\n");
+
+    if let Some(analysis) = analysis {
+        let section = &analysis.section;
+        let (cx, cy) = section.centroid;
+        // Principal axes through the centroid, reaching just past the drawing on either side. The minor axis is given an
+        // angle in the same range as the major one.
+        let minor_angle = if section.principal_angle > 0.0 { section.principal_angle - PI / 2.0 } else { section.principal_angle + PI / 2.0 };
+        let bounds = (min_x - margin / 4.0, min_y - margin / 4.0, max_x + margin / 4.0, max_y + margin / 4.0);
+        let _ = writeln!(out, "    <g class=\"axes\" stroke=\"{}\" fill=\"{}\">", AXIS, AXIS);
+        for (name, angle, dashes) in [("major", section.principal_angle, 4.0), ("minor", minor_angle, 1.5)] {
+            let direction = (angle.cos(), angle.sin());
+            let forward = reach((cx, cy), direction, bounds);
+            let backward = reach((cx, cy), (-direction.0, -direction.1), bounds);
+            let end = (cx + forward * direction.0, cy + forward * direction.1);
+            let _ = writeln!(out, "      <line class=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-dasharray=\"{} {}\"/>",
+                name, cx - backward * direction.0, cy - backward * direction.1, end.0, end.1, dashes * stroke * 4.0, stroke * 4.0);
+            let _ = writeln!(out, "      <text x=\"{}\" y=\"{}\" stroke=\"none\">{} axis, {}°</text>",
+                end.0, end.1, name, label(angle.to_degrees()));
+        }
+        out.push_str("    </g>\n");
+
+        let _ = writeln!(out, "    <g class=\"centroid\" stroke=\"{}\" fill=\"{}\">", STROKE, STROKE);
+        let _ = writeln!(out, "      <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\"/>", cx, cy, font / 3.0);
+        let _ = writeln!(out, "      <path d=\"M{} {} L{} {} M{} {} L{} {}\"/>",
+            cx - font / 2.0, cy, cx + font / 2.0, cy, cx, cy - font / 2.0, cx, cy + font / 2.0);
+        let _ = writeln!(out, "      <text x=\"{}\" y=\"{}\" stroke=\"none\">C ({}, {}){}</text>",
+            cx + font / 2.0, cy - font / 2.0, label(cx), label(cy), unit_suffix);
+        out.push_str("    </g>\n");
+    }
+    out.push_str("  </g>\n</svg>\n");
+    out
+}
+
+// How far from a point inside the box (min x, min y, max x, max y) a ray in the given unit direction leaves it
+fn reach(from: Point, direction: Point, (min_x, min_y, max_x, max_y): (f64, f64, f64, f64)) -> f64 {
+    let along = |start: f64, step: f64, low: f64, high: f64| {
+        if step > 0.0 { (high - start) / step } else if step < 0.0 { (low - start) / step } else { f64::INFINITY }
+    };
+    along(from.0, direction.0, min_x, max_x).min(along(from.1, direction.1, min_y, max_y))
+}
+
+// A number for a label, to three decimal places
+fn label(value: f64) -> String {
+    format::decimals(value, 3)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
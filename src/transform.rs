@@ -0,0 +1,61 @@
+// Affine maps behind ShapeBuilder's translate, rotate, scale and mirror. A point (x, y) goes to
+// (xx * x + xy * y + tx, yx * x + yy * y + ty).
+type Point = (f64, f64);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Affine {
+    xx: f64,
+    xy: f64,
+    yx: f64,
+    yy: f64,
+    tx: f64,
+    ty: f64,
+}
+
+impl Affine {
+    pub(crate) fn translation(dx: f64, dy: f64) -> Affine {
+        Affine { xx: 1.0, xy: 0.0, yx: 0.0, yy: 1.0, tx: dx, ty: dy }
+    }
+
+    // Counter-clockwise by the given angle (with y up) about center. Quarter turns are exact, so a section turned by 90°
+    // keeps its vertices on the same coordinates instead of picking up rounding error.
+    pub(crate) fn rotation(degrees: f64, center: Point) -> Affine {
+        let turned = degrees.rem_euclid(360.0);
+        let (sin, cos) = if turned == 0.0 {
+            (0.0, 1.0)
+        } else if turned == 90.0 {
+            (1.0, 0.0)
+        } else if turned == 180.0 {
+            (0.0, -1.0)
+        } else if turned == 270.0 {
+            (-1.0, 0.0)
+        } else {
+            degrees.to_radians().sin_cos()
+        };
+        Affine { xx: cos, xy: -sin, yx: sin, yy: cos, tx: 0.0, ty: 0.0 }.about(center)
+    }
+
+    // By sx along x and sy along y, away from center
+    pub(crate) fn scaling(sx: f64, sy: f64, center: Point) -> Affine {
+        Affine { xx: sx, xy: 0.0, yx: 0.0, yy: sy, tx: 0.0, ty: 0.0 }.about(center)
+    }
+
+    // Reflection in the infinite line through a and b, which must be different points
+    pub(crate) fn mirror(a: Point, b: Point) -> Affine {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let length_sq = dx * dx + dy * dy;
+        // The cosine and sine of twice the line's angle
+        let (cos, sin) = ((dx * dx - dy * dy) / length_sq, 2.0 * dx * dy / length_sq);
+        Affine { xx: cos, xy: sin, yx: sin, yy: -cos, tx: 0.0, ty: 0.0 }.about(a)
+    }
+
+    pub(crate) fn apply(&self, (x, y): Point) -> Point {
+        (self.xx * x + self.xy * y + self.tx, self.yx * x + self.yy * y + self.ty)
+    }
+
+    // The same linear map, with center instead of the origin staying put
+    fn about(self, center: Point) -> Affine {
+        let moved = self.apply(center);
+        Affine { tx: center.0 - moved.0, ty: center.1 - moved.1, ..self }
+    }
+}
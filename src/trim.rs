@@ -0,0 +1,133 @@
+// Straight runs of edges, for ShapeBuilder::trim and extend. Lines are split wherever another line crosses or touches
+// them, so a line drawn past a corner is stored as several edges in a row. The run through an edge follows it straight on
+// through every vertex where a collinear edge continues it. Vertices where other edges meet the run are its cuts: trim
+// removes the part of the run between the cuts either side of a picked point, and extend lengthens the run at one end.
+use crate::grid::Grid;
+use crate::loops::point_key;
+
+type Point = (f64, f64);
+
+// How far off a run's direction (as a sine) a continuing edge may be, for the rounding of points where lines were split
+const STRAIGHT: f64 = 1e-9;
+
+pub(crate) struct Run {
+    origin: Point,
+    direction: Point,
+    // The run's edges in order along it, each with the positions of its ends along the direction from origin
+    pieces: Vec<(usize, f64, f64)>,
+    // Whether other edges meet the vertex after each piece, one fewer than the pieces
+    cuts: Vec<bool>,
+    // The run's two ends
+    start: Point,
+    end: Point,
+}
+
+impl Run {
+    // The run through the edge at index, which must have length
+    pub(crate) fn through(lines: &[Vec<Point>], grid: &Grid, index: usize) -> Run {
+        let origin = lines[index][0];
+        let (dx, dy) = (lines[index][1].0 - origin.0, lines[index][1].1 - origin.1);
+        let length = dx.hypot(dy);
+        let direction = (dx / length, dy / length);
+        let mut run = Run { origin, direction, pieces: vec![(index, 0.0, length)], cuts: Vec::new(), start: origin, end: lines[index][1] };
+        run.walk(lines, grid, true);
+        run.walk(lines, grid, false);
+        run
+    }
+
+    // Follows the run from one end for as long as an edge continues it straight on
+    fn walk(&mut self, lines: &[Vec<Point>], grid: &Grid, forward: bool) {
+        loop {
+            let (piece, at) = if forward { (self.pieces[self.pieces.len() - 1].0, self.end) } else { (self.pieces[0].0, self.start) };
+            let others: Vec<usize> = incident(lines, grid, at).into_iter().filter(|&other| other != piece).collect();
+            let sign = if forward { 1.0 } else { -1.0 };
+            let next = others.iter().copied().find(|&other| {
+                let far = far_end(&lines[other], at);
+                let (dx, dy) = (far.0 - at.0, far.1 - at.1);
+                let along = (dx * self.direction.0 + dy * self.direction.1) * sign;
+                along > 0.0 && (dx * self.direction.1 - dy * self.direction.0).abs() <= STRAIGHT * dx.hypot(dy)
+            });
+            let Some(next) = next else { return };
+            // A run that comes back round to itself can't be straight, but don't loop forever on bad input
+            if self.pieces.iter().any(|&(index, _, _)| index == next) {
+                return;
+            }
+            let far = far_end(&lines[next], at);
+            let cut = others.len() > 1;
+            if forward {
+                self.pieces.push((next, self.position(at), self.position(far)));
+                self.cuts.push(cut);
+                self.end = far;
+            } else {
+                self.pieces.insert(0, (next, self.position(far), self.position(at)));
+                self.cuts.insert(0, cut);
+                self.start = far;
+            }
+        }
+    }
+
+    fn position(&self, point: Point) -> f64 {
+        (point.0 - self.origin.0) * self.direction.0 + (point.1 - self.origin.1) * self.direction.1
+    }
+
+    // Edges between the cuts either side of the point, projected onto the run, in ascending order
+    pub(crate) fn span(&self, point: Point) -> Vec<usize> {
+        let at = self.position(point);
+        let picked = self.pieces.iter().position(|&(_, _, end)| at <= end).unwrap_or(self.pieces.len() - 1);
+        let mut first = picked;
+        while first > 0 && !self.cuts[first - 1] {
+            first -= 1;
+        }
+        let mut last = picked;
+        while last < self.cuts.len() && !self.cuts[last] {
+            last += 1;
+        }
+        let mut edges: Vec<usize> = self.pieces[first..=last].iter().map(|&(index, _, _)| index).collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    // The line lengthening the run to where it would first meet the segment from a to b, from whichever end is nearer.
+    // None if the run is parallel to the segment, would never reach it, or already reaches it.
+    pub(crate) fn extension(&self, a: Point, b: Point) -> Option<Vec<Point>> {
+        let (ex, ey) = (b.0 - a.0, b.1 - a.1);
+        let denom = self.direction.0 * ey - self.direction.1 * ex;
+        if denom.abs() <= STRAIGHT * ex.hypot(ey) {
+            return None;
+        }
+        let (wx, wy) = (a.0 - self.origin.0, a.1 - self.origin.1);
+        // Where along the segment (0 to 1) the run's infinite line meets it
+        let along = (wx * self.direction.1 - wy * self.direction.0) / denom;
+        if !(0.0..=1.0).contains(&along) {
+            return None;
+        }
+        let hit = if along == 0.0 {
+            a
+        } else if along == 1.0 {
+            b
+        } else {
+            (a.0 + along * ex, a.1 + along * ey)
+        };
+        let at = self.position(hit);
+        if at > self.pieces[self.pieces.len() - 1].2 {
+            Some(vec![self.end, hit])
+        } else if at < self.pieces[0].1 {
+            Some(vec![self.start, hit])
+        } else {
+            None
+        }
+    }
+}
+
+// Edges with an end at the point
+pub(crate) fn incident(lines: &[Vec<Point>], grid: &Grid, point: Point) -> Vec<usize> {
+    let key = point_key(point);
+    grid.near_box(point, point).into_iter()
+        .filter(|&index| lines[index].iter().any(|&end| point_key(end) == key))
+        .collect()
+}
+
+// The end of an edge away from the point, which is at its other end
+pub(crate) fn far_end(line: &[Point], at: Point) -> Point {
+    if point_key(line[0]) == point_key(at) { line[1] } else { line[0] }
+}
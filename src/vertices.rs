@@ -0,0 +1,56 @@
+// Index of the distinct line endpoints, so a vertex's ID and position can be looked up without scanning every line.
+//
+// ShapeBuilder keeps it in step with its lines through place_line, place_lines and take_line, like the grid. A vertex's
+// ID is its position in the list. A new vertex goes on the end, and when the last line ending at a vertex is removed,
+// the last vertex takes its place, so only that one vertex changes ID.
+use std::collections::HashMap;
+use crate::loops::point_key;
+
+type Point = (f64, f64);
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Vertices {
+    points: Vec<Point>,
+    ids: HashMap<(u64, u64), usize>,
+    // Number of line ends at each vertex
+    uses: Vec<usize>,
+}
+
+impl Vertices {
+    pub(crate) fn add(&mut self, line: &[Point]) {
+        for &point in line {
+            let next_id = self.points.len();
+            let id = *self.ids.entry(point_key(point)).or_insert(next_id);
+            if id == next_id {
+                self.points.push(point);
+                self.uses.push(0);
+            }
+            self.uses[id] += 1;
+        }
+    }
+
+    pub(crate) fn remove(&mut self, line: &[Point]) {
+        for &point in line {
+            let Some(&id) = self.ids.get(&point_key(point)) else { continue };
+            self.uses[id] -= 1;
+            if self.uses[id] > 0 {
+                continue;
+            }
+            self.ids.remove(&point_key(point));
+            self.points.swap_remove(id);
+            self.uses.swap_remove(id);
+            if let Some(&moved) = self.points.get(id) {
+                self.ids.insert(point_key(moved), id);
+            }
+        }
+    }
+
+    pub(crate) fn id(&self, point: Point) -> Option<usize> {
+        self.ids.get(&point_key(point)).copied()
+    }
+
+    // Positions in vertex ID order
+    pub(crate) fn points(&self) -> &[Point] {
+        &self.points
+    }
+}
@@ -0,0 +1,28 @@
+// Helpers shared by the test files. Each test file is its own crate and uses only some of these.
+#![allow(dead_code)]
+use centroid_calculator::ShapeBuilder;
+
+pub fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance
+}
+
+// The stored lines with each line's endpoints in ascending order, sorted, so drawings can be compared regardless of how
+// they were built or which way their lines run
+pub fn sorted_lines(shape_builder: &ShapeBuilder) -> Vec<Vec<(f64, f64)>> {
+    let mut lines: Vec<Vec<(f64, f64)>> = shape_builder.lines().to_vec();
+    for line in lines.iter_mut() {
+        line.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+    lines.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    lines
+}
+
+// ID of the vertex at (x, y)
+pub fn vertex_at(shape_builder: &ShapeBuilder, x: f64, y: f64) -> usize {
+    shape_builder.vertices().iter().position(|&point| point == (x, y)).unwrap()
+}
+
+// ID of the edge under (x, y)
+pub fn edge_at(shape_builder: &ShapeBuilder, x: f64, y: f64) -> usize {
+    shape_builder.pick_edge(x, y, 0.01).unwrap().id
+}
@@ -0,0 +1,107 @@
+// Tests for linear and polar arrays of edges
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A perforated plate, with one hole copied into a grid of 4 by 2 holes in a single undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_linear_array() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 10.0, 6.0).unwrap();
+    shape_builder.add_rect(1.0, 1.0, 2.0, 2.0).unwrap();
+    let hole = shape_builder.lines_in_box(0.5, 0.5, 2.5, 2.5);
+    assert_eq!(hole.len(), 4);
+    shape_builder.array_linear(hole, 4, 2, 2.5, 3.0).unwrap();
+
+    assert_eq!(shape_builder.lines().len(), 4 + 8 * 4);
+    let section = shape_builder.section_properties().unwrap();
+    assert!(close(section.area, 52.0, 1e-9));
+    // The holes' centers average (5.25, 3)
+    assert!(close(section.centroid.0, (60.0 * 5.0 - 8.0 * 5.25) / 52.0, 1e-9));
+    assert!(close(section.centroid.1, 3.0, 1e-9));
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 8);
+}
+
+// Test case: Copies that touch are joined like lines drawn there, e.g. a row of squares becoming one strip
+#[wasm_bindgen_test(unsupported = test)]
+fn test_touching_copies() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.array_linear(vec![], 3, 1, 1.0, 0.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 10);
+    assert!(close(shape_builder.area().unwrap(), 3.0, 1e-9));
+}
+
+// Test case: A bolt circle of six holes around the middle of a plate, and quarter turns landing on exact coordinates
+#[wasm_bindgen_test(unsupported = test)]
+fn test_polar_array() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(-10.0, -10.0, 10.0, 10.0).unwrap();
+    shape_builder.add_rect(4.0, -0.5, 5.0, 0.5).unwrap();
+    let hole = shape_builder.lines_in_box(3.5, -1.0, 5.5, 1.0);
+    shape_builder.array_polar(hole, 6, 0.0, 0.0, 60.0).unwrap();
+
+    let section = shape_builder.section_properties().unwrap();
+    assert!(close(section.area, 400.0 - 6.0, 1e-9));
+    assert!(close(section.centroid.0, 0.0, 1e-9) && close(section.centroid.1, 0.0, 1e-9));
+    assert!(close(section.ixx, section.iyy, 1e-6));
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(4.0, -0.5, 5.0, 0.5).unwrap();
+    shape_builder.array_polar(vec![], 4, 0.0, 0.0, 90.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 16);
+    let vertices = shape_builder.vertices();
+    assert!(vertices.contains(&(-0.5, 4.0)) && vertices.contains(&(-5.0, 0.5)) && vertices.contains(&(0.5, -5.0)));
+}
+
+// Test case: A count of zero is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_array_zero_count() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.array_linear(vec![], 0, 2, 1.0, 1.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.array_polar(vec![], 0, 0.0, 0.0, 30.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Unknown edge IDs are rejected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_array_invalid_id() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.array_linear(vec![9], 2, 2, 1.0, 1.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.array_polar(vec![0, 9], 3, 0.0, 0.0, 30.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Non-finite spacings and centers, and copies placed past the largest float, are rejected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_array_nan_input() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.array_linear(vec![], 2, 2, f64::NAN, 1.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.array_polar(vec![], 3, 0.0, f64::INFINITY, 30.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.array_linear(vec![], 3, 1, 1e308, 0.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: A count of one leaves just the original
+#[wasm_bindgen_test(unsupported = test)]
+fn test_array_count_of_one() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    shape_builder.array_linear(vec![], 1, 1, 5.0, 5.0).unwrap();
+    shape_builder.array_polar(vec![], 1, 0.0, 0.0, 45.0).unwrap();
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
@@ -0,0 +1,179 @@
+// Tests for batched edits with deferred intersection resolution
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError; // Errors returned by ShapeBuilder
+mod common;
+use common::{close, sorted_lines};
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Adds the same lines one at a time and as a batch, and checks both drawings match
+fn assert_batch_matches(existing: &[[f64; 4]], new: &[[f64; 4]]) -> ShapeBuilder {
+    let mut sequential = ShapeBuilder::new();
+    let mut batched = ShapeBuilder::new();
+    for &[x1, y1, x2, y2] in existing {
+        sequential.add_line(x1, y1, x2, y2).unwrap();
+        batched.add_line(x1, y1, x2, y2).unwrap();
+    }
+    batched.begin_batch();
+    for &[x1, y1, x2, y2] in new {
+        sequential.add_line(x1, y1, x2, y2).unwrap();
+        batched.add_line(x1, y1, x2, y2).unwrap();
+    }
+    batched.commit_batch().unwrap();
+    assert_eq!(sorted_lines(&batched), sorted_lines(&sequential));
+    batched
+}
+
+// Test case: A grid of crossing lines added in one batch is cut the same way as adding them one by one
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_grid() {
+    let mut new = Vec::new();
+    for i in 1..=3 {
+        new.push([0.0, i as f64, 4.0, i as f64]);
+        new.push([i as f64, 0.0, i as f64, 4.0]);
+    }
+    let shape_builder = assert_batch_matches(&[], &new);
+    assert_eq!(shape_builder.lines().len(), 24);
+}
+
+// Test case: Batched lines split lines that were already in the drawing
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_splits_existing_lines() {
+    let existing = [[0.0, 0.0, 4.0, 0.0], [10.0, 10.0, 11.0, 10.0]];
+    let new = [[1.0, -1.0, 1.0, 1.0], [3.0, 1.0, 3.0, 0.0], [0.0, 0.0, 0.0, 2.0]];
+    let shape_builder = assert_batch_matches(&existing, &new);
+    assert_eq!(shape_builder.lines().len(), 8);
+}
+
+// Test case: Collinear batched lines merge with each other and with existing lines
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_collinear_merge() {
+    let existing = [[0.0, 0.0, 1.0, 0.0], [5.0, 0.0, 6.0, 0.0]];
+    let new = [[0.5, 0.0, 2.0, 0.0], [2.0, 0.0, 3.0, 0.0], [2.5, 0.0, 5.5, 0.0], [2.0, -1.0, 2.0, 1.0]];
+    assert_batch_matches(&existing, &new);
+}
+
+// Test case: Queued lines only appear once the batch is committed
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_deferred_until_commit() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.begin_batch();
+    shape_builder.add_rect(0.0, 2.0, 2.0, 0.0).unwrap();
+    assert!(shape_builder.lines().is_empty());
+
+    shape_builder.commit_batch().unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert_eq!(shape_builder.centroid(), Ok((1.0, 1.0)));
+}
+
+// Test case: A failed edit rolls back the whole batch on commit, including deletes made during it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_failure_rolls_back() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    let before = shape_builder.lines().to_vec();
+
+    shape_builder.begin_batch();
+    shape_builder.delete_line(0.0, 0.0, 1.0, 0.0);
+    shape_builder.add_line(0.0, 1.0, 1.0, 1.0).unwrap();
+    assert_eq!(shape_builder.add_line(0.0, 2.0, f64::NAN, 2.0), Err(ShapeError::NaNInput));
+    // Later edits fail with the first error
+    assert_eq!(shape_builder.add_line(0.0, 3.0, 1.0, 3.0), Err(ShapeError::NaNInput));
+
+    assert_eq!(shape_builder.commit_batch(), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), before.as_slice());
+    assert!(shape_builder.undo());
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: A cancelled batch leaves nothing behind
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_cancel() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.begin_batch();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.clear();
+    shape_builder.cancel_batch();
+
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+    assert_eq!(shape_builder.commit_batch(), Ok(()));
+}
+
+// Test case: A committed batch is undone and redone in one step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_undo_is_one_step() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 1.0, 4.0, 1.0).unwrap();
+    let before = shape_builder.lines().to_vec();
+
+    shape_builder.begin_batch();
+    shape_builder.add_rect(1.0, 2.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, -1.0, 2.0, 3.0).unwrap();
+    shape_builder.commit_batch().unwrap();
+    let after = sorted_lines(&shape_builder);
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines(), before.as_slice());
+    assert!(shape_builder.redo());
+    assert_eq!(sorted_lines(&shape_builder), after);
+}
+
+// Test case: A long line crossing many short ones is cut at each of them, whatever order the lines come in
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_long_line_across_short_lines() {
+    let mut new: Vec<[f64; 4]> = (1..10).rev().map(|i| [i as f64, -1.0, i as f64, 1.0]).collect();
+    new.insert(4, [10.0, 0.0, 0.0, 0.0]);
+    new.push([20.0, -1.0, 20.0, 1.0]);
+    let shape_builder = assert_batch_matches(&[[5.5, 0.5, 5.5, -0.5]], &new);
+    assert_eq!(shape_builder.lines().len(), 11 + 9 * 2 + 2 + 1);
+}
+
+// Test case: In a large drawing, a batch only resolved against nearby lines still merges with a long collinear line and
+// is cut by lines far from where it was drawn
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_in_large_drawing() {
+    // Small squares scattered far from the new lines, so the grid cells are much shorter than the long line
+    let mut existing: Vec<[f64; 4]> = Vec::new();
+    for i in 0..20 {
+        let (x, y) = (i as f64 * 10.0, 50.0);
+        existing.extend([[x, y, x + 1.0, y], [x + 1.0, y, x + 1.0, y + 1.0], [x + 1.0, y + 1.0, x, y + 1.0], [x, y + 1.0, x, y]]);
+    }
+    existing.push([0.0, 0.0, 150.0, 0.0]);
+    existing.push([20.0, 0.0, 20.0, 10.0]);
+    let new = [[140.0, 0.0, 190.0, 0.0], [180.0, -5.0, 180.0, 5.0], [60.0, -5.0, 60.0, 5.0]];
+    let shape_builder = assert_batch_matches(&existing, &new);
+    assert_eq!(shape_builder.lines().len(), 80 + 4 + 1 + 2 + 2);
+}
+
+// Test case: Long parallel lines sharing an x range, with lines crossing all of them, meeting them at T-junctions and
+// sharing their ends, are cut the same way as adding them one by one. Cut points can differ in the last bit, since
+// add_line cuts pieces of lines that were already cut.
+#[wasm_bindgen_test(unsupported = test)]
+fn test_batch_stacked_lines() {
+    let mut lines: Vec<[f64; 4]> = (0..50).map(|i| [0.0, i as f64, 100.0, i as f64]).collect();
+    lines.push([10.0, 0.0, 10.0, 49.0]);
+    lines.push([10.0, 49.0, 20.0, 59.0]);
+    lines.push([60.0, -1.0, 60.0, 60.0]);
+    lines.push([100.0, 0.0, 110.0, 10.0]);
+    lines.push([30.0, 25.5, 40.0, 25.5]);
+    lines.push([50.0, -1.0, 50.0, 20.5]);
+
+    let mut sequential = ShapeBuilder::new();
+    let mut batched = ShapeBuilder::new();
+    batched.begin_batch();
+    for &[x1, y1, x2, y2] in &lines {
+        sequential.add_line(x1, y1, x2, y2).unwrap();
+        batched.add_line(x1, y1, x2, y2).unwrap();
+    }
+    batched.commit_batch().unwrap();
+    // Each long line is cut at x = 10 and 60, and the lower 21 also at x = 50, which cut the crossing lines in turn
+    assert_eq!(batched.lines().len(), 50 * 3 + 21 + 49 + 51 + 22 + 3);
+    for (a, b) in sorted_lines(&batched).iter().zip(sorted_lines(&sequential)) {
+        for (p, q) in a.iter().zip(&b) {
+            assert!(close(p.0, q.0, 1e-9) && close(p.1, q.1, 1e-9), "{:?} != {:?}", a, b);
+        }
+    }
+}
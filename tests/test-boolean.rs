@@ -0,0 +1,132 @@
+// Tests for union, difference, intersection and XOR between drawings
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+fn rect(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> ShapeBuilder {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(min_x, min_y, max_x, max_y).unwrap();
+    shape_builder
+}
+
+// Test case: Each operation on two overlapping squares gives the expected outline, area and centroid
+#[wasm_bindgen_test(unsupported = test)]
+fn test_overlapping_squares() {
+    let other = rect(2.0, 2.0, 6.0, 6.0);
+
+    let mut shape_builder = rect(0.0, 0.0, 4.0, 4.0);
+    shape_builder.union(&other).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(close(shape_builder.area().unwrap(), 28.0, 1e-9));
+    assert_eq!(shape_builder.centroid().unwrap(), (3.0, 3.0));
+
+    let mut shape_builder = rect(0.0, 0.0, 4.0, 4.0);
+    shape_builder.difference(&other).unwrap();
+    assert_eq!(shape_builder.lines().len(), 6);
+    assert!(close(shape_builder.area().unwrap(), 12.0, 1e-9));
+
+    let mut shape_builder = rect(0.0, 0.0, 4.0, 4.0);
+    shape_builder.intersection(&other).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert_eq!(shape_builder.centroid().unwrap(), (3.0, 3.0));
+
+    // A square and the same square turned by 45° overlap in an octagon
+    let mut turned = rect(0.0, 0.0, 4.0, 4.0);
+    turned.rotate(vec![], 45.0, 2.0, 2.0).unwrap();
+    let mut union = rect(0.0, 0.0, 4.0, 4.0);
+    union.union(&turned).unwrap();
+    let mut intersection = rect(0.0, 0.0, 4.0, 4.0);
+    intersection.intersection(&turned).unwrap();
+    assert_eq!((union.lines().len(), intersection.lines().len()), (16, 8));
+    let octagon = 32.0 * (std::f64::consts::SQRT_2 - 1.0);
+    assert!(close(intersection.area().unwrap(), octagon, 1e-9));
+    assert!(close(union.area().unwrap(), 32.0 - octagon, 1e-9));
+
+    // Squares overlapping across their full height leave two separate pieces
+    let mut shape_builder = rect(0.0, 0.0, 4.0, 2.0);
+    shape_builder.xor(&rect(2.0, 0.0, 6.0, 2.0)).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 8.0, 1e-9));
+    assert_eq!(shape_builder.loops().unwrap().len(), 2);
+}
+
+// Test case: Cutting a square out of a larger one gives a polygon with a hole
+#[wasm_bindgen_test(unsupported = test)]
+fn test_holes() {
+    let inner = rect(2.0, 2.0, 4.0, 4.0);
+
+    let mut shape_builder = rect(0.0, 0.0, 10.0, 10.0);
+    shape_builder.difference(&inner).unwrap();
+    assert_eq!(shape_builder.to_wkt(), "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (2 2, 2 4, 4 4, 4 2, 2 2))");
+    assert!(close(shape_builder.area().unwrap(), 96.0, 1e-9));
+
+    // XOR gives the same, and the hole is filled again by a union
+    let mut xor = rect(0.0, 0.0, 10.0, 10.0);
+    xor.xor(&inner).unwrap();
+    assert_eq!(xor.to_wkt(), shape_builder.to_wkt());
+    shape_builder.union(&inner).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(close(shape_builder.area().unwrap(), 100.0, 1e-9));
+
+    // The intersection is the inner square
+    let mut shape_builder = rect(0.0, 0.0, 10.0, 10.0);
+    shape_builder.intersection(&inner).unwrap();
+    assert_eq!(shape_builder.to_wkt(), "POLYGON ((2 2, 4 2, 4 4, 2 4, 2 2))");
+}
+
+// Test case: A plate welded onto a beam, minus two bolt holes, matches the same section drawn edge by edge
+#[wasm_bindgen_test(unsupported = test)]
+fn test_built_up_section() {
+    let mut section = rect(0.0, 0.0, 10.0, 20.0);
+    section.union(&rect(-2.0, 20.0, 12.0, 22.0)).unwrap();
+    section.difference(&rect(1.0, 20.5, 2.0, 21.5)).unwrap();
+    section.difference(&rect(8.0, 20.5, 9.0, 21.5)).unwrap();
+    assert_eq!(section.lines().len(), 16);
+
+    let mut drawn = ShapeBuilder::new();
+    for (min_x, min_y, max_x, max_y) in [(0.0, 0.0, 10.0, 20.0), (-2.0, 20.0, 12.0, 22.0), (1.0, 20.5, 2.0, 21.5), (8.0, 20.5, 9.0, 21.5)] {
+        drawn.add_rect(min_x, min_y, max_x, max_y).unwrap();
+    }
+    let (expected, found) = (drawn.section_properties().unwrap(), section.section_properties().unwrap());
+    assert!(close(found.area, 226.0, 1e-9));
+    assert!(close(found.centroid.0, 5.0, 1e-9) && close(found.centroid.1, expected.centroid.1, 1e-9));
+    assert!(close(found.ixx, expected.ixx, 1e-6) && close(found.iyy, expected.iyy, 1e-6) && close(found.ixy, 0.0, 1e-6));
+
+    // Each operation is one undo step
+    assert!(section.undo());
+    assert!(section.undo());
+    assert!(close(section.area().unwrap(), 228.0, 1e-9));
+}
+
+// Test case: Lines that don't enclose anything are dropped from the result
+#[wasm_bindgen_test(unsupported = test)]
+fn test_stray_lines_dropped() {
+    let mut shape_builder = rect(0.0, 0.0, 2.0, 2.0);
+    shape_builder.add_line(3.0, 0.0, 3.0, 2.0).unwrap();
+    shape_builder.union(&ShapeBuilder::new()).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+}
+
+// Test case: The XOR of two overlapping squares has a hole cornered by the two pieces, which can't be drawn, so it fails
+// without changing anything
+#[wasm_bindgen_test(unsupported = test)]
+fn test_touching_hole() {
+    let mut shape_builder = rect(0.0, 0.0, 4.0, 4.0);
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.xor(&rect(2.0, 2.0, 6.0, 6.0)), Err(ShapeError::TouchingHole));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+    assert!(shape_builder.undo());
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: Disjoint regions have no intersection
+#[wasm_bindgen_test(unsupported = test)]
+fn test_disjoint_intersection() {
+    let mut shape_builder = rect(0.0, 0.0, 1.0, 1.0);
+    shape_builder.intersection(&rect(5.0, 5.0, 6.0, 6.0)).unwrap();
+    assert!(shape_builder.lines().is_empty());
+}
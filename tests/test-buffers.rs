@@ -0,0 +1,53 @@
+// Tests for the packed vertex and edge buffers and the version counter
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Vertices are packed once each in vertex ID order, and edges refer to them by ID
+#[wasm_bindgen_test(unsupported = test)]
+fn test_packed_buffers() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, 0.0, 2.0, 1.0).unwrap();
+    shape_builder.add_line(2.0, 1.0, 0.0, 0.0).unwrap();
+
+    assert_eq!(shape_builder.packed_vertices(), &[0.0, 0.0, 2.0, 0.0, 2.0, 1.0]);
+    assert_eq!(shape_builder.packed_edges(), &[0, 1, 1, 2, 2, 0]);
+
+    let vertices = shape_builder.vertices();
+    let packed = shape_builder.packed_vertices().to_vec();
+    assert_eq!(packed.chunks(2).map(|xy| (xy[0], xy[1])).collect::<Vec<_>>(), vertices);
+}
+
+// Test case: The buffers rebuild only for a new version, which every change to the lines (and only those) produces
+#[wasm_bindgen_test(unsupported = test)]
+fn test_version_tracks_changes() {
+    let mut shape_builder = ShapeBuilder::new();
+    let empty = shape_builder.version();
+    assert!(shape_builder.packed_edges().is_empty());
+
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    let one_line = shape_builder.version();
+    assert_ne!(one_line, empty);
+    assert_eq!(shape_builder.packed_edges(), &[0, 1]);
+
+    // Nothing changes: failed edits, deleting a line that isn't there, reading properties
+    assert!(shape_builder.add_line(0.0, 0.0, 0.0, 0.0).is_err());
+    shape_builder.delete_line(5.0, 5.0, 6.0, 6.0);
+    let _ = shape_builder.centroid();
+    assert_eq!(shape_builder.version(), one_line);
+
+    // Splitting the line gives a new version and new buffers
+    shape_builder.add_line(0.5, -1.0, 0.5, 1.0).unwrap();
+    assert_ne!(shape_builder.version(), one_line);
+    assert_eq!(shape_builder.packed_edges().len(), 8);
+
+    // Undo restores the lines, with yet another version
+    let split = shape_builder.version();
+    assert!(shape_builder.undo());
+    assert_ne!(shape_builder.version(), split);
+    assert_eq!(shape_builder.packed_vertices(), &[0.0, 0.0, 1.0, 0.0]);
+    assert_eq!(shape_builder.packed_edges(), &[0, 1]);
+}
@@ -0,0 +1,51 @@
+// Tests for caching the loops, area, centroid and section properties between edits
+use std::rc::Rc;
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError; // Errors returned by ShapeBuilder
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Results are reused until the lines actually change, then recomputed
+#[wasm_bindgen_test(unsupported = test)]
+fn test_cache_follows_edits() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 2.0, 2.0, 0.0).unwrap();
+    let loops = shape_builder.loops().unwrap();
+    assert!(Rc::ptr_eq(&loops, &shape_builder.loops().unwrap()));
+
+    // Edits that change nothing keep the cached results
+    assert!(shape_builder.add_line(0.0, 0.0, f64::NAN, 0.0).is_err());
+    shape_builder.delete_line(7.0, 7.0, 8.0, 8.0);
+    assert!(Rc::ptr_eq(&loops, &shape_builder.loops().unwrap()));
+
+    // Splitting the square in two changes the loops
+    shape_builder.add_line(1.0, 0.0, 1.0, 2.0).unwrap();
+    let split = shape_builder.loops().unwrap();
+    assert!(!Rc::ptr_eq(&loops, &split));
+    assert_eq!(split.len(), 2);
+    assert_eq!(shape_builder.centroid(), Ok((1.0, 1.0)));
+
+    // Undo and clear are edits as well
+    shape_builder.undo();
+    assert_eq!(shape_builder.loops().unwrap().len(), 1);
+    shape_builder.clear();
+    assert_eq!(shape_builder.area(), Err(ShapeError::Degenerate));
+}
+
+// Test case: Section properties come from the same cache as the loops, and are recomputed with them
+#[wasm_bindgen_test(unsupported = test)]
+fn test_section_properties_cached() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 2.0, 2.0, 0.0).unwrap();
+    let before = shape_builder.section_properties().unwrap();
+    assert_eq!(shape_builder.section_properties().unwrap(), before);
+
+    shape_builder.add_rect(2.0, 2.0, 4.0, 0.0).unwrap();
+    let after = shape_builder.section_properties().unwrap();
+    assert_eq!(after.area, 8.0);
+    assert_eq!(after.centroid, (2.0, 1.0));
+    shape_builder.undo();
+    assert_eq!(shape_builder.section_properties().unwrap(), before);
+}
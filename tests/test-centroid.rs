@@ -0,0 +1,81 @@
+// Tests for the area centroid of the region enclosed by the lines. calculate_centroid used to return the average of the
+// line endpoints instead; these pin down shapes where the two agree and shapes where they differ.
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: For a triangle and a rectangle the area centroid is also the average of the corners, as it was before
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_regular_shapes() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(3.0, 0.0, 0.0, 3.0).unwrap();
+    shape_builder.add_line(0.0, 3.0, 0.0, 0.0).unwrap();
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!((x - 1.0).abs() < 1e-9);
+    assert!((y - 1.0).abs() < 1e-9);
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 2.0, 6.0, 0.0).unwrap();
+    assert_eq!(shape_builder.centroid(), Ok((3.0, 1.0)));
+}
+
+// Test case: The centroid is area weighted, not the average of the vertices
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_area_weighted() {
+    let mut shape_builder = ShapeBuilder::new();
+    // L shape: a 4x1 foot and a 1x3 upright, split into two rectangles by an internal line
+    shape_builder.add_line(0.0, 0.0, 4.0, 0.0).unwrap();
+    shape_builder.add_line(4.0, 0.0, 4.0, 1.0).unwrap();
+    shape_builder.add_line(4.0, 1.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(1.0, 1.0, 1.0, 4.0).unwrap();
+    shape_builder.add_line(1.0, 4.0, 0.0, 4.0).unwrap();
+    shape_builder.add_line(0.0, 4.0, 0.0, 0.0).unwrap();
+    shape_builder.add_line(0.0, 1.0, 1.0, 1.0).unwrap();
+
+    // (4 * (2, 0.5) + 3 * (0.5, 2.5)) / 7
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!((x - 9.5 / 7.0).abs() < 1e-9);
+    assert!((y - 9.5 / 7.0).abs() < 1e-9);
+}
+
+// Test case: A loop drawn inside another loop is a hole
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_with_hole() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 4.0, 4.0, 0.0).unwrap();
+    shape_builder.add_rect(2.0, 3.0, 3.0, 1.0).unwrap();
+
+    // 16 minus a 1x2 hole centered at (2.5, 2): x = (16 * 2 - 2 * 2.5) / 14
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!((x - 27.0 / 14.0).abs() < 1e-9);
+    assert!((y - 2.0).abs() < 1e-9);
+}
+
+// Test case: Splitting an edge into many pieces adds vertices but doesn't move the centroid
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_ignores_extra_vertices() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 1.0, 1.0, 0.0).unwrap();
+    // Crossing stubs split the bottom edge at x = 0.1, 0.2, ..., 0.9; the endpoint average would sink towards them
+    for i in 1..10 {
+        let x = i as f64 / 10.0;
+        shape_builder.add_line(x, 0.0, x, -0.1).unwrap();
+    }
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!((x - 0.5).abs() < 1e-9);
+    assert!((y - 0.5).abs() < 1e-9);
+}
+
+// Test case: Lines that don't enclose anything don't move the centroid
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_ignores_dangling_lines() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 2.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, 2.0, 10.0, 10.0).unwrap();
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!((x - 1.0).abs() < 1e-9);
+    assert!((y - 1.0).abs() < 1e-9);
+}
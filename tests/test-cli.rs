@@ -0,0 +1,69 @@
+// Tests for the centroid-calc command-line tool
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// Writes files for a test into a directory of its own, returning their paths
+fn write_files(test: &str, files: &[(&str, &str)]) -> Vec<PathBuf> {
+    let directory = std::env::temp_dir().join(format!("centroid-calc-{}-{}", test, std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    files.iter().map(|(name, contents)| {
+        let path = directory.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }).collect()
+}
+
+fn run(args: &[&str], files: &[PathBuf]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_centroid-calc")).args(args).args(files).output().unwrap()
+}
+
+// Test case: Files of different types are read by extension and reported in each format
+#[test]
+fn test_formats() {
+    let files = write_files("formats", &[
+        ("plate.csv", "x,y\n0,0\n4,0\n4,2\n0,2\n"),
+        ("parcel.wkt", "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))"),
+        ("part.svg", r#"<svg xmlns="http://www.w3.org/2000/svg"><rect x="0" y="0" width="2" height="6"/></svg>"#),
+    ]);
+
+    let output = run(&["--format", "csv"], &files);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "file,units,area,centroid_x,centroid_y,ixx,iyy,ixy,i_major,i_minor,principal_angle_deg");
+    assert!(lines[1].ends_with("plate.csv,,8,2,1,2.6666666666666665,10.666666666666666,0,10.666666666666666,2.666666666666666,90"));
+    assert!(lines[2].contains("parcel.wkt,,100,5,5,"));
+    assert!(lines[3].contains("part.svg,,12,1,3,"));
+
+    let output = run(&["--format=json"], &files[..1]);
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results[0]["area"], 8.0);
+    assert_eq!(results[0]["centroid"], serde_json::json!([2.0, 1.0]));
+
+    let output = run(&[], &files);
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.starts_with("file"));
+    assert!(text.lines().nth(1).unwrap().contains("2.66667"));
+}
+
+// Test case: Failing files are reported on stderr while the rest are still printed, and bad arguments are refused
+#[test]
+fn test_failures() {
+    let files = write_files("failures", &[
+        ("good.csv", "0 0\n1 0\n1 1\n0 1\n"),
+        ("open.csv", "x,y\n0,0\n1,one\n"),
+        ("notes.doc", "not a drawing"),
+    ]);
+
+    let output = run(&["-f", "csv"], &files);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 2);
+    let errors = String::from_utf8(output.stderr).unwrap();
+    assert!(errors.contains("open.csv") && errors.contains("notes.doc"));
+
+    assert_eq!(run(&["--format", "xml"], &files).status.code(), Some(2));
+    assert_eq!(run(&["--tolerance", "-1"], &files).status.code(), Some(2));
+    assert_eq!(run(&[], &[]).status.code(), Some(2));
+    assert!(run(&["--help"], &[]).status.success());
+}
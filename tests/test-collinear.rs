@@ -0,0 +1,138 @@
+// Tests for merging collinear lines in add_line. These only use the native API, so they run both natively and in the browser
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+mod common;
+use common::sorted_lines;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Collinear lines with a gap between them are kept apart
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_disjoint() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, 0.0, 3.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (1.0, 0.0)], vec![(2.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: A new line touching the end of an existing line merges into one line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_touching_after() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 3.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: A new line touching the start of an existing line merges into one line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_touching_before() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(1.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 0.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: A new line overlapping the end of an existing line extends it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_partial_overlap_after() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 3.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: A new line overlapping the start of an existing line extends it (this used to compare only against one endpoint)
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_partial_overlap_before() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(1.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: A new line inside an existing line leaves the existing extent untouched
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_new_contained() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 4.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 2.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (4.0, 0.0)]]);
+}
+
+// Test case: A new line covering an existing line replaces it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_existing_contained() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(1.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(4.0, 0.0, 0.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (4.0, 0.0)]]);
+}
+
+// Test case: Adding the same line again, in either direction, doesn't duplicate it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_identical() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, 0.0, 0.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (2.0, 0.0)]]);
+}
+
+// Test case: A new line bridging the gap between two collinear lines merges all three
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_bridge() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(0.5, 0.0, 2.5, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: Merging works along vertical and diagonal lines too
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_vertical_and_diagonal() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(5.0, 1.0, 5.0, 3.0).unwrap();
+    shape_builder.add_line(5.0, 0.0, 5.0, 2.0).unwrap();
+    shape_builder.add_line(10.0, 10.0, 12.0, 12.0).unwrap();
+    shape_builder.add_line(13.0, 13.0, 11.0, 11.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(5.0, 0.0), (5.0, 3.0)], vec![(10.0, 10.0), (13.0, 13.0)]]);
+}
+
+// Test case: Parallel lines that are not on the same infinite line are never merged
+#[wasm_bindgen_test(unsupported = test)]
+fn test_parallel_offset_not_merged() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 1.0, 3.0, 1.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![vec![(0.0, 0.0), (2.0, 0.0)], vec![(1.0, 1.0), (3.0, 1.0)]]);
+}
+
+// Test case: A merged line is still split where another line crosses it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_collinear_merge_then_split() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(1.0, -1.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 2.0, 0.0).unwrap();
+
+    assert_eq!(sorted_lines(&shape_builder), vec![
+        vec![(0.0, 0.0), (1.0, 0.0)],
+        vec![(1.0, -1.0), (1.0, 0.0)],
+        vec![(1.0, 0.0), (1.0, 1.0)],
+        vec![(1.0, 0.0), (2.0, 0.0)],
+    ]);
+}
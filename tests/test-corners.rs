@@ -0,0 +1,134 @@
+// Tests for filleting and chamfering corners
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+use std::f64::consts::PI;
+mod common;
+use common::{close, vertex_at};
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Filleting a square's corner replaces it with an arc tangent to both sides, in one undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_fillet() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    let corner = vertex_at(&shape_builder, 4.0, 4.0);
+    shape_builder.fillet(corner, 1.0).unwrap();
+
+    // The arc is drawn as straight pieces within 1e-4 of the radius, which cut off a little more than the true arc
+    assert!(close(shape_builder.area().unwrap(), 16.0 - (1.0 - PI / 4.0), 1e-3));
+    let vertices = shape_builder.vertices();
+    assert!(!vertices.contains(&(4.0, 4.0)));
+    assert!(vertices.contains(&(4.0, 3.0)) && vertices.contains(&(3.0, 4.0)));
+    // Every new vertex is on the arc
+    for &(x, y) in vertices.iter().filter(|&&(x, y)| x > 3.0 && y > 3.0) {
+        assert!(close((x - 3.0).hypot(y - 3.0), 1.0, 1e-9));
+    }
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(shape_builder.can_redo());
+}
+
+// Test case: Root fillets where a T-section's web meets its flange add material on either side of the web
+#[wasm_bindgen_test(unsupported = test)]
+fn test_root_fillets() {
+    let mut shape_builder = ShapeBuilder::new();
+    let outline = [(0.0, 8.0), (0.0, 10.0), (10.0, 10.0), (10.0, 8.0), (6.0, 8.0), (6.0, 0.0), (4.0, 0.0), (4.0, 8.0)];
+    for i in 0..outline.len() {
+        let (a, b) = (outline[i], outline[(i + 1) % outline.len()]);
+        shape_builder.add_line(a.0, a.1, b.0, b.1).unwrap();
+    }
+    let before = shape_builder.section_properties().unwrap();
+    for (x, y) in [(4.0, 8.0), (6.0, 8.0)] {
+        let root = vertex_at(&shape_builder, x, y);
+        shape_builder.fillet(root, 1.5).unwrap();
+    }
+
+    let after = shape_builder.section_properties().unwrap();
+    let fillet_area = 1.5 * 1.5 * (1.0 - PI / 4.0);
+    assert!(close(after.area, before.area + 2.0 * fillet_area, 1e-3));
+    assert!(close(after.centroid.0, 5.0, 1e-9));
+    // The fillets sit above the centroid, near the flange, so they pull it up
+    assert!(after.centroid.1 > before.centroid.1);
+}
+
+// Test case: Chamfers cut the corner straight across, d1 along the edge with the lower ID, and may take a whole edge
+#[wasm_bindgen_test(unsupported = test)]
+fn test_chamfer() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    let corner = vertex_at(&shape_builder, 4.0, 4.0);
+    shape_builder.chamfer(corner, 1.0, 2.0).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 15.0, 1e-9));
+    assert!(shape_builder.vertices().contains(&(4.0, 3.0)) && shape_builder.vertices().contains(&(2.0, 4.0)));
+    assert_eq!(shape_builder.lines().len(), 5);
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    let corner = vertex_at(&shape_builder, 2.0, 2.0);
+    shape_builder.chamfer(corner, 2.0, 2.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 3);
+    assert!(close(shape_builder.area().unwrap(), 2.0, 1e-9));
+}
+
+// Test case: An unknown vertex ID is rejected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_corner_invalid_id() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.fillet(99, 1.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.chamfer(99, 1.0, 1.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: A non-finite radius or distance is rejected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_corner_nan_input() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    let corner = vertex_at(&shape_builder, 2.0, 2.0);
+    assert_eq!(shape_builder.fillet(corner, f64::NAN), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.chamfer(corner, 1.0, f64::INFINITY), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: A radius or distance that isn't positive is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_corner_not_positive() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    let corner = vertex_at(&shape_builder, 2.0, 2.0);
+    assert_eq!(shape_builder.fillet(corner, 0.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.chamfer(corner, 1.0, -1.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: A fillet or chamfer longer than the edges is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_corner_too_large() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    let corner = vertex_at(&shape_builder, 2.0, 2.0);
+    assert_eq!(shape_builder.fillet(corner, 2.5), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.chamfer(corner, 1.0, 3.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Vertices where three edges meet, or only one, aren't corners
+#[wasm_bindgen_test(unsupported = test)]
+fn test_not_a_corner() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, -1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.fillet(vertex_at(&shape_builder, 1.0, 0.0), 0.1), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.chamfer(vertex_at(&shape_builder, 1.0, -1.0), 0.1, 0.1), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
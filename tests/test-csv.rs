@@ -0,0 +1,78 @@
+// Tests for importing plain-text coordinate lists
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Rings separated by blank lines are closed into loops, so an inner ring cuts a hole
+#[wasm_bindgen_test(unsupported = test)]
+fn test_rings() {
+    let mut shape_builder = ShapeBuilder::new();
+    let text = "# outer boundary\nx,y\n0,0\n10,0\n10,10\n0,10\n\n\n// hole\n2,2\n4,2\n4,4\n2,4\n2,2\n";
+    shape_builder.import_csv(text).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(close(shape_builder.area().unwrap(), 96.0, 1e-9));
+
+    // The whole import is one undo step
+    assert!(shape_builder.undo());
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Tabs, whitespace, and semicolons with decimal commas are all recognised
+#[wasm_bindgen_test(unsupported = test)]
+fn test_delimiters() {
+    for text in [
+        "0\t0\n3\t0\n3\t1.5\n0\t1.5\n",
+        "\n  0   0\n  3 0\r\n3    1.5\n0 1.5",
+        "0;0\n3;0\n3;1,5\n0;1,5\n",
+        "\u{feff}\"x\",\"y\"\n\"0\",\"0\"\n\"3\",\"0\"\n\"3\",\"1.5\"\n\"0\",\"1.5\"\n",
+    ] {
+        let mut shape_builder = ShapeBuilder::new();
+        shape_builder.import_csv(text).unwrap();
+        assert!(close(shape_builder.area().unwrap(), 4.5, 1e-9), "{:?}", text);
+    }
+}
+
+// Test case: A header naming the columns picks out x and y among others
+#[wasm_bindgen_test(unsupported = test)]
+fn test_header_columns() {
+    let mut shape_builder = ShapeBuilder::new();
+    let text = "Point,Northing,Easting,Elevation\n1,100,500,12.5\n2,100,504,12.5\n3,102,504,13.0\n";
+    shape_builder.import_csv(text).unwrap();
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 502.0 + 2.0 / 3.0, 1e-9) && close(y, 100.0 + 2.0 / 3.0, 1e-9));
+}
+
+// Test case: A value that isn't a number is reported at its line, and nothing is added
+#[wasm_bindgen_test(unsupported = test)]
+fn test_not_a_number() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.import_csv("x,y\n0,0\n1,0\n1,one\n"), Err(ShapeError::Parse {
+        line: Some(4),
+        message: "expected a number, found \"one\"".to_string(),
+    }));
+    assert!(matches!(shape_builder.import_csv("0,0\n1,0\nNaN,1\n"), Err(ShapeError::Parse { line: Some(3), .. })));
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: A line with only one value is reported at its line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_missing_value() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_csv("0,0\n1,0\n1\n"), Err(ShapeError::Parse { line: Some(3), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: A ring needs three distinct points, and one without them is reported at its first line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_short_ring() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_csv("0,0\n1,0\n1,1\n\n5,5\n6,6\n5,5\n"), Err(ShapeError::Parse { line: Some(5), .. })));
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+}
@@ -0,0 +1,125 @@
+// Tests for saving and opening drawings as JSON documents
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::{Part, ShapeError};
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A drawing is saved one segment per line and opens again with the same lines, units, metadata and parts
+#[wasm_bindgen_test(unsupported = test)]
+fn test_round_trip() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 4.0, 0.0).unwrap();
+    shape_builder.add_line(4.0, 0.0, 0.1, 3.0).unwrap();
+    shape_builder.set_units("mm");
+    shape_builder.set_metadata("project", "Bridge deck");
+    shape_builder.set_metadata("author", "A. Engineer");
+    shape_builder.add_part("web", 0.5, 2.0).unwrap();
+
+    let json = shape_builder.to_json();
+    assert_eq!(json, r#"{
+  "format": "centroid-calculator",
+  "version": 2,
+  "units": "mm",
+  "metadata": {
+    "author": "A. Engineer",
+    "project": "Bridge deck"
+  },
+  "parts": [
+    {"name":"web","at":[0.5,2.0]}
+  ],
+  "segments": [
+    [0.0,0.0,4.0,0.0],
+    [4.0,0.0,0.1,3.0]
+  ]
+}"#);
+
+    let opened = ShapeBuilder::from_json(&json).unwrap();
+    assert_eq!(opened.lines(), shape_builder.lines());
+    assert_eq!(opened.units(), "mm");
+    assert_eq!(opened.get_metadata("project"), Some("Bridge deck".to_string()));
+    assert_eq!(opened.parts(), &[Part { name: "web".to_string(), at: (0.5, 2.0) }]);
+    assert!(!opened.can_undo());
+    assert_eq!(opened.to_json(), json);
+}
+
+// Test case: Version 1 documents, as written by serializing ShapeBuilder with serde, are migrated
+#[wasm_bindgen_test(unsupported = test)]
+fn test_migrate_version_1() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 2.0, 2.0, 0.0).unwrap();
+    let legacy = serde_json::to_string(&shape_builder).unwrap();
+    assert!(legacy.starts_with(r#"{"lines":"#));
+
+    let opened = ShapeBuilder::from_json(&legacy).unwrap();
+    assert_eq!(opened.lines(), shape_builder.lines());
+    assert_eq!(opened.units(), "");
+    assert!(opened.parts().is_empty());
+    assert!(opened.to_json().contains(r#""version": 2"#));
+}
+
+// Test case: Deserializing a ShapeBuilder with serde checks each saved line like add_line, instead of panicking later
+#[wasm_bindgen_test(unsupported = test)]
+fn test_deserialize_malformed_lines() {
+    let read = |json: &str| serde_json::from_str::<ShapeBuilder>(json);
+    let error = read(r#"{"lines": [[[0, 0]]]}"#).err().unwrap().to_string();
+    assert!(error.contains("line 0 has 1 points instead of 2"), "{}", error);
+    assert!(read(r#"{"lines": [[[0, 0], [1, 0], [2, 0]]]}"#).is_err());
+    let error = read(r#"{"lines": [[[0, 0], [0, 0]]]}"#).err().unwrap().to_string();
+    assert!(error.contains(&ShapeError::Degenerate.to_string()), "{}", error);
+
+    let shape_builder = read(r#"{"lines": [[[-1, 0], [1, 0]], [[0, -1], [0, 1]]]}"#).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: A segment add_line would reject fails with add_line's own error, not a Parse error
+#[wasm_bindgen_test(unsupported = test)]
+fn test_open_rejects_degenerate_segment() {
+    let json = r#"{"format": "centroid-calculator", "version": 2, "segments": [[0, 0, 1, 0], [1, 1, 1, 1]]}"#;
+    assert_eq!(ShapeBuilder::from_json(json).err(), Some(ShapeError::Degenerate));
+}
+
+// Test case: Segments that cross in a hand-edited document are split when it is opened
+#[wasm_bindgen_test(unsupported = test)]
+fn test_open_splits_crossing_segments() {
+    let json = r#"{"format": "centroid-calculator", "version": 2, "segments": [[-1, 0, 1, 0], [0, -1, 0, 1]]}"#;
+    let opened = ShapeBuilder::from_json(json).unwrap();
+    assert_eq!(opened.lines().len(), 4);
+}
+
+// Test case: Unreadable, foreign, newer and malformed documents are rejected with a Parse error
+#[wasm_bindgen_test(unsupported = test)]
+fn test_open_errors() {
+    let kind = |json: &str| ShapeBuilder::from_json(json).err().map(|error| error.kind());
+    assert_eq!(kind("{\n  \"version\": 2,\n  oops\n}"), Some("Parse"));
+    assert_eq!(kind(r#"{"format": "something-else", "version": 2, "segments": []}"#), Some("Parse"));
+    assert_eq!(kind(r#"{"format": "centroid-calculator", "version": 3, "segments": []}"#), Some("Parse"));
+    assert_eq!(kind(r#"{"lines": [[[0, 0]]]}"#), Some("Parse"));
+    assert_eq!(kind("[]"), Some("Parse"));
+
+    match ShapeBuilder::from_json("{\n  \"version\": 2,\n  oops\n}") {
+        Err(ShapeError::Parse { line, .. }) => assert_eq!(line, Some(3)),
+        _ => panic!("expected a parse error"),
+    }
+}
+
+// Test case: Parts and metadata can be renamed, moved and removed
+#[wasm_bindgen_test(unsupported = test)]
+fn test_parts_and_metadata() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_part("flange", 0.0, 0.0).unwrap();
+    shape_builder.add_part("web", 1.0, 1.0).unwrap();
+    shape_builder.add_part("flange", 2.0, 0.0).unwrap();
+    assert_eq!(shape_builder.part_names(), vec!["flange".to_string(), "web".to_string()]);
+    assert_eq!(shape_builder.parts()[0].at, (2.0, 0.0));
+    assert_eq!(shape_builder.add_part("bad", f64::NAN, 0.0), Err(ShapeError::NaNInput));
+
+    assert!(shape_builder.remove_part("web"));
+    assert!(!shape_builder.remove_part("web"));
+
+    shape_builder.set_metadata("rev", "A");
+    assert!(shape_builder.remove_metadata("rev"));
+    assert_eq!(shape_builder.get_metadata("rev"), None);
+}
@@ -0,0 +1,188 @@
+// Tests for importing and exporting DXF files
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// A DXF file with the given $INSUNITS (if any) and entities, each entity written as its group code and value pairs
+fn dxf(units: Option<i64>, entities: &[&[(i32, &str)]]) -> String {
+    let mut out = String::new();
+    let mut pair = |code: i32, value: &str| out.push_str(&format!("{:>3}\n{}\n", code, value));
+    if let Some(units) = units {
+        pair(0, "SECTION");
+        pair(2, "HEADER");
+        pair(9, "$INSUNITS");
+        pair(70, &units.to_string());
+        pair(0, "ENDSEC");
+    }
+    pair(0, "SECTION");
+    pair(2, "ENTITIES");
+    for entity in entities {
+        for &(code, value) in entity.iter() {
+            pair(code, value);
+        }
+    }
+    pair(0, "ENDSEC");
+    pair(0, "EOF");
+    out
+}
+
+// Test case: LINE entities and a closed LWPOLYLINE make up rectangles, and the file's units are taken on
+#[wasm_bindgen_test(unsupported = test)]
+fn test_lines_and_polylines() {
+    let mut shape_builder = ShapeBuilder::new();
+    let file = dxf(Some(4), &[
+        &[(0, "LINE"), (8, "0"), (10, "0"), (20, "0"), (30, "0"), (11, "4"), (21, "0"), (31, "0")],
+        &[(0, "LINE"), (8, "0"), (10, "4"), (20, "0"), (11, "4"), (21, "2")],
+        &[(0, "LINE"), (8, "0"), (10, "4"), (20, "2"), (11, "0"), (21, "2")],
+        &[(0, "LINE"), (8, "0"), (10, "0"), (20, "2"), (11, "0"), (21, "0")],
+        &[(0, "LWPOLYLINE"), (8, "0"), (90, "4"), (70, "1"), (10, "10"), (20, "0"), (10, "12"), (20, "0"), (10, "12"), (20, "2"), (10, "10"), (20, "2")],
+    ]);
+    shape_builder.import_dxf(&file, Vec::new(), 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(close(shape_builder.area().unwrap(), 12.0, 1e-9));
+    assert_eq!(shape_builder.units(), "mm");
+}
+
+// Test case: Bulges, arcs and circles are flattened within the tolerance
+#[wasm_bindgen_test(unsupported = test)]
+fn test_curves() {
+    // A slot: two straight sides joined by half circles of radius 1, drawn with bulges of 1
+    let mut shape_builder = ShapeBuilder::new();
+    let file = dxf(None, &[
+        &[(0, "LWPOLYLINE"), (8, "0"), (90, "4"), (70, "1"), (10, "0"), (20, "0"), (10, "4"), (20, "0"), (42, "1"), (10, "4"), (20, "2"), (10, "0"), (20, "2"), (42, "1")],
+    ]);
+    shape_builder.import_dxf(&file, Vec::new(), 0.0001).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 8.0 + std::f64::consts::PI, 0.001));
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 2.0, 1e-6) && close(y, 1.0, 1e-6));
+    assert_eq!(shape_builder.units(), "");
+
+    // A half disc: an arc over the top from 0 to 180 degrees, closed by a line
+    let mut shape_builder = ShapeBuilder::new();
+    let file = dxf(None, &[
+        &[(0, "ARC"), (8, "0"), (10, "0"), (20, "0"), (40, "2"), (50, "0"), (51, "180")],
+        &[(0, "LINE"), (8, "0"), (10, "-2"), (20, "0"), (11, "2"), (21, "0")],
+    ]);
+    shape_builder.import_dxf(&file, Vec::new(), 0.0001).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 2.0 * std::f64::consts::PI, 0.01));
+    let (_, y) = shape_builder.centroid().unwrap();
+    assert!(close(y, 8.0 / (3.0 * std::f64::consts::PI), 0.01));
+
+    let mut shape_builder = ShapeBuilder::new();
+    let file = dxf(None, &[&[(0, "CIRCLE"), (8, "0"), (10, "5"), (20, "5"), (40, "1")]]);
+    shape_builder.import_dxf(&file, Vec::new(), 0.001).unwrap();
+    assert!(close(shape_builder.area().unwrap(), std::f64::consts::PI, 0.01));
+}
+
+// Test case: Only entities on the requested layers are read, matching names regardless of case
+#[wasm_bindgen_test(unsupported = test)]
+fn test_layers() {
+    let file = dxf(None, &[
+        &[(0, "LWPOLYLINE"), (8, "Section"), (90, "3"), (70, "1"), (10, "0"), (20, "0"), (10, "6"), (20, "0"), (10, "0"), (20, "6")],
+        &[(0, "LINE"), (8, "Dimensions"), (10, "-1"), (20, "-1"), (11, "7"), (21, "-1")],
+        &[(0, "TEXT"), (8, "Section"), (10, "1"), (20, "1"), (1, "Web")],
+    ]);
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_dxf(&file, vec!["SECTION".to_string()], 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 3);
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_dxf(&file, Vec::new(), 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+}
+
+// Test case: A file in metres read into a drawing in millimetres is scaled, and unconvertible units are refused at the
+// line giving them
+#[wasm_bindgen_test(unsupported = test)]
+fn test_units() {
+    let file = dxf(Some(6), &[&[(0, "LINE"), (8, "0"), (10, "0"), (20, "0"), (11, "0.25"), (21, "0")]]);
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.set_units("mm");
+    shape_builder.import_dxf(&file, Vec::new(), 0.01).unwrap();
+    assert_eq!(shape_builder.lines(), &[vec![(0.0, 0.0), (250.0, 0.0)]]);
+    assert_eq!(shape_builder.units(), "mm");
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.set_units("px");
+    // The error points at the $INSUNITS value: SECTION, HEADER and $INSUNITS take lines 1 to 6, and 70 is on line 7
+    assert!(matches!(shape_builder.import_dxf(&file, Vec::new(), 0.01), Err(ShapeError::Parse { line: Some(8), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: A value that isn't a number is reported at its line, and nothing is added
+#[wasm_bindgen_test(unsupported = test)]
+fn test_not_a_number() {
+    let mut shape_builder = ShapeBuilder::new();
+    let file = dxf(None, &[&[(0, "LINE"), (8, "0"), (10, "0"), (20, "zero"), (11, "1"), (21, "1")]]);
+    // SECTION, ENTITIES, LINE and the layer take the first 8 lines and 10 the next two, so 20 is on line 11 and its value on 12
+    assert_eq!(shape_builder.import_dxf(&file, Vec::new(), 0.01), Err(ShapeError::Parse {
+        line: Some(12),
+        message: "expected a number for group code 20, found \"zero\"".to_string(),
+    }));
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: An entity missing a coordinate is reported at the entity's line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_missing_coordinate() {
+    let mut shape_builder = ShapeBuilder::new();
+    let file = dxf(None, &[&[(0, "LINE"), (8, "0"), (10, "0"), (20, "0")]]);
+    assert!(matches!(shape_builder.import_dxf(&file, Vec::new(), 0.01), Err(ShapeError::Parse { line: Some(5), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: A group code that isn't a number is reported at its line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_bad_group_code() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_dxf("0\nSECTION\nnot a code\n", Vec::new(), 0.01), Err(ShapeError::Parse { line: Some(3), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: The exported GEOMETRY layer imports again as the same lines, in the same units
+#[wasm_bindgen_test(unsupported = test)]
+fn test_export_round_trip() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 3.0).unwrap();
+    shape_builder.add_line(0.5, 0.1, 3.25, 2.75).unwrap();
+    shape_builder.set_units("in");
+    let file = shape_builder.to_dxf();
+    assert!(file.starts_with("  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1009\n"));
+    assert!(file.ends_with("  0\nENDSEC\n  0\nEOF\n"));
+
+    let mut imported = ShapeBuilder::new();
+    imported.import_dxf(&file, vec!["GEOMETRY".to_string()], 0.01).unwrap();
+    assert_eq!(imported.lines(), shape_builder.lines());
+    assert_eq!(imported.units(), "in");
+}
+
+// Test case: The centroid, principal axes and properties go on their own layers
+#[wasm_bindgen_test(unsupported = test)]
+fn test_export_layers() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 10.0, 4.0).unwrap();
+    shape_builder.set_units("mm");
+    let file = shape_builder.to_dxf();
+
+    assert!(file.contains("  0\nPOINT\n  8\nCENTROID\n 10\n5\n 20\n2\n 30\n0\n"));
+    assert!(file.contains("Area = 40 mm^2"));
+    assert!(file.contains("Centroid = (5, 2) mm"));
+    assert!(file.contains("Iyy = 333.3333 mm^4"));
+    // Both axes are lines through the centroid on the AXES layer
+    let mut axes = ShapeBuilder::new();
+    axes.import_dxf(&file, vec!["AXES".to_string()], 0.01).unwrap();
+    assert_eq!(axes.lines().len(), 4);
+    assert!(axes.vertices().contains(&(5.0, 2.0)));
+
+    // Lines that don't enclose anything are written without the centroid, axes or properties
+    let mut open = ShapeBuilder::new();
+    open.add_line(0.0, 0.0, 1.0, 1.0).unwrap();
+    let file = open.to_dxf();
+    assert!(!file.contains("POINT") && !file.contains("  8\nAXES") && !file.contains("TEXT"));
+}
@@ -0,0 +1,93 @@
+// Tests for the errors returned by the native ShapeBuilder API
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError; // Errors returned by ShapeBuilder
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: NaN and infinite coordinates are rejected without touching the shape
+#[wasm_bindgen_test(unsupported = test)]
+fn test_add_line_nan_input() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+
+    assert_eq!(shape_builder.add_line(0.5, -1.0, f64::NAN, 1.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.add_line(0.5, -1.0, 0.5, f64::INFINITY), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.add_rect(f64::NAN, 1.0, 1.0, 0.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &[vec![(0.0, 0.0), (1.0, 0.0)]]);
+}
+
+// Test case: Lines of zero length and rectangles without width or height are degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_degenerate_input() {
+    let mut shape_builder = ShapeBuilder::new();
+
+    assert_eq!(shape_builder.add_line(1.0, 1.0, 1.0, 1.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.add_rect(0.0, 5.0, 0.0, 0.0), Err(ShapeError::Degenerate));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: The centroid of an empty shape or one with fewer than three lines is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_degenerate() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.centroid(), Err(ShapeError::Degenerate));
+
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, 1.0).unwrap();
+    assert_eq!(shape_builder.centroid(), Err(ShapeError::Degenerate));
+}
+
+// Test case: Lines that don't enclose anything are not closed
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_not_closed() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(1.0, 1.0, 0.0, 1.0).unwrap();
+
+    assert_eq!(shape_builder.centroid(), Err(ShapeError::NotClosed));
+}
+
+// Test case: A sliver far thinner than the drawing has no usable area
+#[wasm_bindgen_test(unsupported = test)]
+fn test_centroid_zero_area() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 0.5, 1e-17).unwrap();
+    shape_builder.add_line(0.5, 1e-17, 0.0, 0.0).unwrap();
+
+    assert_eq!(shape_builder.centroid(), Err(ShapeError::ZeroArea));
+}
+
+// Test case: A parse error names its line when it has one, and describes the document as a whole when it doesn't
+#[wasm_bindgen_test(unsupported = test)]
+fn test_parse_error_line() {
+    let on_line = ShapeError::Parse { line: Some(3), message: "expected a number".to_string() };
+    assert_eq!(on_line.to_string(), "could not read line 3: expected a number");
+    let anywhere = ShapeError::Parse { line: None, message: "unknown format".to_string() };
+    assert_eq!(anywhere.to_string(), "could not read the document: unknown format");
+}
+
+// Test case: Errors thrown to Javascript carry their kind as a field and their description as the message, and a parse
+// error its line
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen_test]
+fn test_js_error_fields() {
+    use wasm_bindgen::JsValue;
+    let field = |error: &JsValue, name: &str| js_sys::Reflect::get(error, &JsValue::from_str(name)).unwrap();
+
+    let error: JsValue = ShapeError::NaNInput.into();
+    assert_eq!(field(&error, "name").as_string().as_deref(), Some("ShapeError"));
+    assert_eq!(field(&error, "kind").as_string().as_deref(), Some("NaNInput"));
+
+    let error: JsValue = ShapeError::Parse { line: Some(3), message: "expected a number".to_string() }.into();
+    assert_eq!(field(&error, "kind").as_string().as_deref(), Some("Parse"));
+    assert_eq!(field(&error, "line").as_f64(), Some(3.0));
+    assert_eq!(field(&error, "message").as_string().as_deref(), Some("could not read line 3: expected a number"));
+
+    // A problem that isn't on any particular line has a null line
+    let error: JsValue = ShapeError::Parse { line: None, message: "unknown format".to_string() }.into();
+    assert!(field(&error, "line").is_null());
+}
@@ -0,0 +1,132 @@
+// Tests for reading and writing Well-Known Text and GeoJSON
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A WKT polygon with a hole, and a multipolygon, give the expected area and centroid
+#[wasm_bindgen_test(unsupported = test)]
+fn test_read_wkt() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_wkt("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (2 2, 2 4, 4 4, 4 2, 2 2))").unwrap();
+    assert!(close(shape_builder.area().unwrap(), 96.0, 1e-9));
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, (500.0 - 12.0) / 96.0, 1e-9) && close(y, (500.0 - 12.0) / 96.0, 1e-9));
+
+    // Extended WKT with an SRID, Z values, lower case and a ring that isn't closed
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_wkt("SRID=3857;multipolygon z (((0 0 5, 2 0 5, 2 2 5)), EMPTY, ((10 0 1, 12 0 1, 12 2 1, 10 2 1, 10 0 1)))").unwrap();
+    assert!(close(shape_builder.area().unwrap(), 6.0, 1e-9));
+
+    shape_builder.import_wkt("POLYGON EMPTY").unwrap();
+    assert_eq!(shape_builder.lines().len(), 7);
+}
+
+// Test case: GeoJSON polygons are read from geometries, Features and FeatureCollections, skipping other geometry types
+#[wasm_bindgen_test(unsupported = test)]
+fn test_read_geojson() {
+    let mut shape_builder = ShapeBuilder::new();
+    let geojson = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": {"parcel": "12"}, "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [4, 0], [4, 3], [0, 3], [0, 0]]]}},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [2, 1]}},
+            {"type": "Feature", "properties": {}, "geometry": null},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "MultiPolygon", "coordinates": [[[[10, 0], [11, 0], [11, 1], [10, 1], [10, 0]]]]}}
+        ]
+    }"#;
+    shape_builder.import_geojson(geojson).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(close(shape_builder.area().unwrap(), 13.0, 1e-9));
+}
+
+// Test case: Faces sharing an edge are written as one polygon, holes as inner rings, separate pieces as a multipolygon
+#[wasm_bindgen_test(unsupported = test)]
+fn test_write_wkt() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.to_wkt(), "POLYGON EMPTY");
+
+    // A square split in two by a line across it is still one polygon
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_line(2.0, 0.0, 2.0, 4.0).unwrap();
+    assert_eq!(shape_builder.to_wkt(), "POLYGON ((0 0, 2 0, 4 0, 4 4, 2 4, 0 4, 0 0))");
+
+    // A hole away from the dividing line, and a separate square
+    shape_builder.add_rect(2.5, 1.0, 3.5, 3.0).unwrap();
+    shape_builder.add_rect(10.0, 0.0, 11.0, 1.0).unwrap();
+    let wkt = shape_builder.to_wkt();
+    assert_eq!(wkt, "MULTIPOLYGON (((0 0, 2 0, 4 0, 4 4, 2 4, 0 4, 0 0), (2.5 1, 2.5 3, 3.5 3, 3.5 1, 2.5 1)), ((10 0, 11 0, 11 1, 10 1, 10 0)))");
+
+    // Reading it back gives the same region
+    let mut reread = ShapeBuilder::new();
+    reread.import_wkt(&wkt).unwrap();
+    assert!(close(reread.area().unwrap(), shape_builder.area().unwrap(), 1e-9));
+    assert_eq!(reread.centroid().unwrap(), shape_builder.centroid().unwrap());
+}
+
+// Test case: GeoJSON output is a Feature with the area, centroid and units, and reads back as the same region
+#[wasm_bindgen_test(unsupported = test)]
+fn test_write_geojson() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.to_geojson(), r#"{"geometry":null,"properties":{},"type":"Feature"}"#);
+
+    shape_builder.add_rect(0.0, 0.0, 4.0, 2.0).unwrap();
+    shape_builder.add_rect(1.0, 0.5, 2.0, 1.5).unwrap();
+    shape_builder.set_units("m");
+    let geojson = shape_builder.to_geojson();
+    assert_eq!(geojson, concat!(
+        r#"{"geometry":{"coordinates":[[[0.0,0.0],[4.0,0.0],[4.0,2.0],[0.0,2.0],[0.0,0.0]],"#,
+        r#"[[1.0,0.5],[1.0,1.5],[2.0,1.5],[2.0,0.5],[1.0,0.5]]],"type":"Polygon"},"#,
+        r#""properties":{"area":7.0,"centroid":[2.0714285714285716,1.0],"units":"m"},"type":"Feature"}"#,
+    ));
+
+    let mut reread = ShapeBuilder::new();
+    reread.import_geojson(&geojson).unwrap();
+    assert!(close(reread.area().unwrap(), 7.0, 1e-9));
+}
+
+// Test case: A WKT value that isn't a number is reported at its line, and nothing is added
+#[wasm_bindgen_test(unsupported = test)]
+fn test_wkt_not_a_number() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.import_wkt("POLYGON ((0 0, 1 0,\n 1 x, 0 0))"), Err(ShapeError::Parse { line: Some(2), message: "expected a number".to_string() }));
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: WKT geometry that isn't a polygon is refused
+#[wasm_bindgen_test(unsupported = test)]
+fn test_wkt_not_a_polygon() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_wkt("LINESTRING (0 0, 1 1)"), Err(ShapeError::Parse { line: Some(1), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Text after a complete WKT geometry is refused
+#[wasm_bindgen_test(unsupported = test)]
+fn test_wkt_trailing_text() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_wkt("POLYGON ((0 0, 1 0, 1 1)) extra"), Err(ShapeError::Parse { .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Valid JSON with the wrong GeoJSON structure is refused without a line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_geojson_bad_structure() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_geojson("{\"type\": \"Polygon\",\n\"coordinates\": [[[0, 0], [1]]]}"), Err(ShapeError::Parse { line: None, .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Invalid JSON is reported at its line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_geojson_invalid_json() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_geojson("{\n\"type\": "), Err(ShapeError::Parse { line: Some(2), .. })));
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+}
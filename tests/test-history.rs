@@ -0,0 +1,124 @@
+// Tests for undo/redo of ShapeBuilder edits
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Undo removes an added line and redo brings it back
+#[wasm_bindgen_test(unsupported = test)]
+fn test_undo_redo_add_line() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(!shape_builder.can_undo());
+
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    assert!(shape_builder.undo());
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+
+    assert!(shape_builder.redo());
+    assert_eq!(shape_builder.lines(), &[vec![(0.0, 0.0), (1.0, 0.0)]]);
+    assert!(!shape_builder.redo());
+}
+
+// Test case: Undoing a crossing line also undoes the split it made, restoring the original line in its original place
+#[wasm_bindgen_test(unsupported = test)]
+fn test_undo_restores_split_lines() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 1.0, 0.0, -1.0).unwrap();
+    shape_builder.add_line(5.0, 5.0, 6.0, 5.0).unwrap();
+    let before = shape_builder.lines().to_vec();
+
+    shape_builder.add_line(-1.0, 0.0, 1.0, 0.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 5);
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines(), before.as_slice());
+
+    assert!(shape_builder.redo());
+    assert_eq!(shape_builder.lines().len(), 5);
+}
+
+// Test case: Undoing a merged collinear line brings back the lines it absorbed
+#[wasm_bindgen_test(unsupported = test)]
+fn test_undo_restores_merged_lines() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(2.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(0.5, 0.0, 2.5, 0.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 1);
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines(), &[vec![(0.0, 0.0), (1.0, 0.0)], vec![(2.0, 0.0), (3.0, 0.0)]]);
+}
+
+// Test case: A rectangle is undone in one step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_undo_rect_is_one_step() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(10.0, 10.0, 11.0, 11.0).unwrap();
+    shape_builder.add_rect(0.0, 5.0, 5.0, 0.0).unwrap();
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines(), &[vec![(10.0, 10.0), (11.0, 11.0)]]);
+}
+
+// Test case: Delete and clear can be undone
+#[wasm_bindgen_test(unsupported = test)]
+fn test_undo_delete_and_clear() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 5.0, 5.0, 0.0).unwrap();
+    let rect = shape_builder.lines().to_vec();
+
+    shape_builder.delete_line(5.0, 5.0, 5.0, 0.0);
+    shape_builder.clear();
+    assert!(shape_builder.lines().is_empty());
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 3);
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines(), rect.as_slice());
+}
+
+// Test case: Making a new edit after undoing discards the redo history
+#[wasm_bindgen_test(unsupported = test)]
+fn test_new_edit_clears_redo() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.undo();
+    assert!(shape_builder.can_redo());
+
+    shape_builder.add_line(0.0, 1.0, 1.0, 1.0).unwrap();
+    assert!(!shape_builder.can_redo());
+    assert!(!shape_builder.redo());
+}
+
+// Test case: Edits between begin_action and end_action are undone together, and history is unbounded
+#[wasm_bindgen_test(unsupported = test)]
+fn test_grouped_action_and_many_undos() {
+    let mut shape_builder = ShapeBuilder::new();
+    for i in 0..50 {
+        shape_builder.add_line(0.0, i as f64, 1.0, i as f64).unwrap();
+    }
+    shape_builder.begin_action();
+    shape_builder.add_line(5.0, 0.0, 5.0, 1.0).unwrap();
+    shape_builder.add_line(6.0, 0.0, 6.0, 1.0).unwrap();
+    shape_builder.end_action();
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 50);
+    for _ in 0..50 {
+        assert!(shape_builder.undo());
+    }
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.undo());
+}
+
+// Test case: Rejected edits don't leave anything to undo
+#[wasm_bindgen_test(unsupported = test)]
+fn test_failed_edit_not_recorded() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(shape_builder.add_line(0.0, 0.0, f64::NAN, 1.0).is_err());
+    assert!(shape_builder.add_rect(0.0, 0.0, 0.0, 1.0).is_err());
+    assert!(!shape_builder.can_undo());
+}
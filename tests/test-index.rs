@@ -0,0 +1,133 @@
+// Tests for the spatial lookups backed by ShapeBuilder's grid index
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Distance from a point to a line, checked the slow way
+fn distance(x: f64, y: f64, line: &[(f64, f64)]) -> f64 {
+    let ((ax, ay), (bx, by)) = (line[0], line[1]);
+    let t = (((x - ax) * (bx - ax) + (y - ay) * (by - ay)) / ((bx - ax).powi(2) + (by - ay).powi(2))).clamp(0.0, 1.0);
+    (x - ax - t * (bx - ax)).hypot(y - ay - t * (by - ay))
+}
+
+// Test case: The nearest line and vertex are found within the radius, and nothing outside it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_nearest_line_and_vertex() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 4.0, 4.0, 0.0).unwrap();
+    shape_builder.add_line(10.0, 0.0, 12.0, 2.0).unwrap();
+
+    let (index, distance) = shape_builder.nearest_line(2.0, 3.5, 1.0).unwrap();
+    assert_eq!(shape_builder.lines()[index], vec![(0.0, 4.0), (4.0, 4.0)]);
+    assert!((distance - 0.5).abs() < 1e-12);
+
+    let (vertex, distance) = shape_builder.nearest_vertex(3.8, 0.1, 0.5).unwrap();
+    assert_eq!(vertex, (4.0, 0.0));
+    assert!((distance - 0.05f64.sqrt()).abs() < 1e-12);
+    assert_eq!(shape_builder.nearest_vertex(2.0, 2.0, 1.0), None);
+    assert_eq!(shape_builder.nearest_line(7.0, 2.0, 1.0), None);
+
+    let (index, _) = shape_builder.nearest_line(11.0, 1.0, 0.1).unwrap();
+    assert_eq!(shape_builder.lines()[index], vec![(10.0, 0.0), (12.0, 2.0)]);
+}
+
+// Test case: Box queries return the lines whose bounding boxes overlap the box
+#[wasm_bindgen_test(unsupported = test)]
+fn test_lines_in_box() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(5.0, 5.0, 6.0, 6.0).unwrap();
+    shape_builder.add_line(0.0, 10.0, 10.0, 10.0).unwrap();
+
+    assert_eq!(shape_builder.lines_in_box(-1.0, -1.0, 0.5, 0.5), vec![0]);
+    assert_eq!(shape_builder.lines_in_box(4.0, 4.0, 9.0, 10.0), vec![1, 2]);
+    assert_eq!(shape_builder.lines_in_box(2.0, 1.0, 4.0, 4.0), Vec::<usize>::new());
+    assert_eq!(shape_builder.lines_in_box(-1e9, -1e9, 1e9, 1e9), vec![0, 1, 2]);
+}
+
+// Test case: Lookups agree with checking every line, after splits, merges, deletes, undo and redo
+#[wasm_bindgen_test(unsupported = test)]
+fn test_index_matches_every_line() {
+    let mut shape_builder = ShapeBuilder::new();
+    for i in 0..40 {
+        let (x, y) = ((i * 7 % 23) as f64, (i * 11 % 19) as f64);
+        shape_builder.add_line(x, y, x + (i % 5) as f64 - 2.0, y + 3.0).unwrap();
+        if i % 6 == 0 {
+            shape_builder.undo();
+        }
+        if i % 9 == 0 {
+            shape_builder.redo();
+        }
+    }
+    shape_builder.add_line(-100.0, 10.5, 100.0, 10.5).unwrap();
+    let line = shape_builder.lines()[3].clone();
+    shape_builder.delete_line(line[0].0, line[0].1, line[1].0, line[1].1);
+
+    for step in 0..200 {
+        let (x, y) = ((step % 25) as f64 - 0.3, (step / 10) as f64 + 0.4);
+        let expected = shape_builder.lines().iter().map(|line| distance(x, y, line)).fold(f64::INFINITY, f64::min);
+        match shape_builder.nearest_line(x, y, 2.0) {
+            Some((index, found)) => {
+                assert!((found - expected).abs() < 1e-12);
+                assert!((distance(x, y, &shape_builder.lines()[index]) - expected).abs() < 1e-12);
+            }
+            None => assert!(expected > 2.0),
+        }
+    }
+}
+
+// Test case: Vertex IDs from pick and vertices() and the packed buffers agree with the stored lines, after splits, merges,
+// deletes in the middle, undo and redo
+#[wasm_bindgen_test(unsupported = test)]
+fn test_vertex_index_matches_lines() {
+    let mut shape_builder = ShapeBuilder::new();
+    for i in 0..30 {
+        let (x, y) = ((i * 5 % 17) as f64, (i * 3 % 13) as f64);
+        shape_builder.add_line(x, y, x + 4.0, y + (i % 3) as f64).unwrap();
+        if i % 4 == 0 {
+            let line = shape_builder.lines()[0].clone();
+            shape_builder.delete_line(line[0].0, line[0].1, line[1].0, line[1].1);
+        }
+        if i % 7 == 0 {
+            shape_builder.undo();
+        }
+        if i % 11 == 0 {
+            shape_builder.redo();
+        }
+    }
+
+    let vertices = shape_builder.vertices();
+    let mut distinct: Vec<(f64, f64)> = shape_builder.lines().iter().flatten().copied().collect();
+    distinct.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    distinct.dedup();
+    let mut sorted = vertices.clone();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    assert_eq!(sorted, distinct);
+
+    for (id, &(x, y)) in vertices.iter().enumerate() {
+        let pick = shape_builder.pick(x, y, 1e-9).unwrap();
+        assert_eq!(pick.id, id);
+    }
+    let lines = shape_builder.lines().to_vec();
+    let edges = shape_builder.packed_edges().to_vec();
+    for (line, ends) in lines.iter().zip(edges.chunks(2)) {
+        assert_eq!((vertices[ends[0] as usize], vertices[ends[1] as usize]), (line[0], line[1]));
+    }
+}
+
+// Test case: A line far longer than a small drawing is resolved without walking every cell along it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_long_line_in_small_drawing() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 1.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(0.0, 0.5, 1e7, 0.5).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(shape_builder.lines().contains(&vec![(1.0, 0.5), (1e7, 0.5)]));
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.001, 0.001, 0.0).unwrap();
+    shape_builder.add_rect(-1e6, 1e6, 1e6, -1e6).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+}
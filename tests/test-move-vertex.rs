@@ -0,0 +1,96 @@
+// Tests for moving vertices, as when dragging a corner on the canvas
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::{close, vertex_at};
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Dragging a corner moves both edges that meet there and keeps the loop closed, in one undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_drag_corner() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    let corner = vertex_at(&shape_builder, 4.0, 4.0);
+
+    shape_builder.move_vertex(corner, 6.0, 4.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(close(shape_builder.area().unwrap(), 20.0, 1e-9));
+    assert_eq!(shape_builder.lines().iter().flatten().filter(|&&point| point == (6.0, 4.0)).count(), 2);
+
+    assert!(shape_builder.undo());
+    assert!(close(shape_builder.area().unwrap(), 16.0, 1e-9));
+    assert!(shape_builder.redo());
+    assert!(close(shape_builder.area().unwrap(), 20.0, 1e-9));
+
+    // Moving a vertex to where it already is changes nothing
+    let version = shape_builder.version();
+    let corner = vertex_at(&shape_builder, 6.0, 4.0);
+    shape_builder.move_vertex(corner, 6.0, 4.0).unwrap();
+    assert_eq!(shape_builder.version(), version);
+}
+
+// Test case: Edges dragged across other edges are split where they cross, and dropping a vertex on another joins them
+#[wasm_bindgen_test(unsupported = test)]
+fn test_new_intersections() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_line(6.0, 0.0, 6.0, 4.0).unwrap();
+
+    // Dragging the corner past the free line splits it and the two moved edges where they cross it
+    let corner = vertex_at(&shape_builder, 4.0, 4.0);
+    shape_builder.move_vertex(corner, 8.0, 4.0).unwrap();
+    let mut expected = ShapeBuilder::new();
+    expected.add_line(0.0, 0.0, 4.0, 0.0).unwrap();
+    expected.add_line(4.0, 0.0, 8.0, 4.0).unwrap();
+    expected.add_line(8.0, 4.0, 0.0, 4.0).unwrap();
+    expected.add_line(0.0, 4.0, 0.0, 0.0).unwrap();
+    expected.add_line(6.0, 0.0, 6.0, 4.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), expected.lines().len());
+    assert!(close(shape_builder.area().unwrap(), expected.area().unwrap(), 1e-9));
+
+    // Dropping the free line's ends onto the square's corners connects it, and once it lies along the right side the two
+    // are merged into one edge
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_line(6.0, 0.0, 6.0, 4.0).unwrap();
+    let end = vertex_at(&shape_builder, 6.0, 0.0);
+    shape_builder.move_vertex(end, 4.0, 0.0).unwrap();
+    assert_eq!(shape_builder.vertices().len(), 5);
+    let end = vertex_at(&shape_builder, 6.0, 4.0);
+    shape_builder.move_vertex(end, 4.0, 4.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(close(shape_builder.area().unwrap(), 16.0, 1e-9));
+
+    // An edge whose ends are dragged together disappears
+    shape_builder.add_line(8.0, 0.0, 8.0, 1.0).unwrap();
+    let end = vertex_at(&shape_builder, 8.0, 1.0);
+    shape_builder.move_vertex(end, 8.0, 0.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+}
+
+// Test case: An unknown vertex ID fails without changing anything or adding an undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_move_invalid_id() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.move_vertex(4, 2.0, 2.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+    assert!(shape_builder.undo());
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: A non-finite target fails without changing anything or adding an undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_move_nan_input() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.move_vertex(0, f64::INFINITY, 2.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+    assert!(shape_builder.undo());
+    assert!(!shape_builder.can_undo());
+}
@@ -0,0 +1,134 @@
+// Tests for growing and shrinking regions by a distance
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::{Join, ShapeBuilder}; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+fn square() -> ShapeBuilder {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder
+}
+
+// Test case: A square grows to a bigger square with miter joins, with its corners cut off by square joins, and with
+// rounded corners by round joins
+#[wasm_bindgen_test(unsupported = test)]
+fn test_grow() {
+    let mut shape_builder = square();
+    shape_builder.offset(1.0, Join::Miter, 0.01).unwrap();
+    assert_eq!(shape_builder.to_wkt(), "POLYGON ((-1 -1, 5 -1, 5 5, -1 5, -1 -1))");
+
+    // Each corner is cut across at 1 from the original corner
+    let mut shape_builder = square();
+    shape_builder.offset(1.0, Join::Square, 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(close(shape_builder.area().unwrap(), 24.0 + 8.0 * std::f64::consts::SQRT_2, 1e-9));
+
+    let mut shape_builder = square();
+    shape_builder.offset(1.0, Join::Round, 0.001).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 16.0 + 16.0 + std::f64::consts::PI, 0.01));
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 2.0, 1e-9) && close(y, 2.0, 1e-9));
+    assert!(shape_builder.lines().iter().flatten().all(|&(x, y)| (x - 2.0).abs() <= 3.0 && (y - 2.0).abs() <= 3.0));
+
+    // The inside corner of an L moves out diagonally
+    let mut shape_builder = ShapeBuilder::new();
+    let corners = [(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (2.0, 2.0), (2.0, 4.0), (0.0, 4.0)];
+    for i in 0..corners.len() {
+        let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+        shape_builder.add_line(a.0, a.1, b.0, b.1).unwrap();
+    }
+    shape_builder.offset(1.0, Join::Miter, 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 6);
+    assert!(close(shape_builder.area().unwrap(), 32.0, 1e-9));
+    assert!(shape_builder.vertices().contains(&(3.0, 3.0)));
+}
+
+// Test case: Shrinking takes the distance off every face, including around holes, and removes parts that are too thin
+#[wasm_bindgen_test(unsupported = test)]
+fn test_shrink() {
+    for join in [Join::Miter, Join::Round, Join::Square] {
+        let mut shape_builder = square();
+        shape_builder.offset(-1.0, join, 0.01).unwrap();
+        assert_eq!(shape_builder.to_wkt(), "POLYGON ((1 1, 3 1, 3 3, 1 3, 1 1))");
+    }
+
+    // A hollow section losing 0.1 on every face: the outside shrinks and the hole grows
+    let mut hollow = ShapeBuilder::new();
+    hollow.add_rect(0.0, 0.0, 10.0, 6.0).unwrap();
+    hollow.add_rect(1.0, 1.0, 9.0, 5.0).unwrap();
+    let mut shape_builder = hollow.clone();
+    shape_builder.offset(-0.1, Join::Miter, 0.01).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 9.8 * 5.8 - 8.2 * 4.2, 1e-9));
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 5.0, 1e-9) && close(y, 3.0, 1e-9));
+
+    // Round joins round off the hole's corners, leaving a little more material
+    let mut shape_builder = hollow.clone();
+    shape_builder.offset(-0.1, Join::Round, 1e-5).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 9.8 * 5.8 - 8.2 * 4.2 + 0.04 - 0.01 * std::f64::consts::PI, 1e-4));
+
+    // A plate thinner than twice the distance is eaten away
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 10.0, 1.0).unwrap();
+    shape_builder.offset(-0.6, Join::Miter, 0.01).unwrap();
+    assert!(shape_builder.lines().is_empty());
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 4);
+}
+
+// Test case: Miters longer than four times the distance are cut off
+#[wasm_bindgen_test(unsupported = test)]
+fn test_miter_limit() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 10.0, 0.0).unwrap();
+    shape_builder.add_line(10.0, 0.0, 0.0, 1.0).unwrap();
+    shape_builder.add_line(0.0, 1.0, 0.0, 0.0).unwrap();
+    shape_builder.offset(1.0, Join::Miter, 0.01).unwrap();
+    let furthest = shape_builder.vertices().iter().map(|point| point.0).fold(f64::NEG_INFINITY, f64::max);
+    assert!(furthest > 10.0 && furthest <= 11.0);
+    // The right-angled corners are still mitered
+    assert!(shape_builder.vertices().contains(&(-1.0, -1.0)));
+}
+
+// Test case: A non-finite distance is rejected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_offset_nan_input() {
+    let mut shape_builder = square();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.offset(f64::NAN, Join::Miter, 0.01), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: A tolerance that isn't positive is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_offset_bad_tolerance() {
+    let mut shape_builder = square();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.offset(1.0, Join::Round, 0.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: An offset of zero changes nothing
+#[wasm_bindgen_test(unsupported = test)]
+fn test_offset_zero() {
+    let mut shape_builder = square();
+    let version = shape_builder.version();
+    shape_builder.offset(0.0, Join::Miter, 0.01).unwrap();
+    assert_eq!(shape_builder.version(), version);
+}
+
+// Test case: Lines that don't enclose a region can't be offset
+#[wasm_bindgen_test(unsupported = test)]
+fn test_offset_not_closed() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(1.0, 1.0, 2.0, 1.0).unwrap();
+    assert_eq!(shape_builder.offset(1.0, Join::Miter, 0.01), Err(ShapeError::NotClosed));
+    assert_eq!(shape_builder.lines().len(), 3);
+}
@@ -0,0 +1,82 @@
+// Tests for picking vertices and edges, and deleting lines by ID
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::{Pick, PickKind, ShapeError};
+mod common;
+use common::vertex_at;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A vertex ID is the vertex's position in vertices, and only holds until the next edit: removing the last
+// line at a vertex hands its ID to another vertex
+#[wasm_bindgen_test(unsupported = test)]
+fn test_vertex_ids() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(0.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(1.0, 1.0, 2.0, 1.0).unwrap();
+
+    let corner = vertex_at(&shape_builder, 1.0, 1.0);
+    let pick = shape_builder.pick(1.1, 1.05, 0.2).unwrap();
+    assert_eq!((pick.kind, pick.id, pick.x, pick.y), (PickKind::Vertex, corner, 1.0, 1.0));
+
+    // (0, 0) loses both its lines, and the last vertex takes its ID
+    let removed = vertex_at(&shape_builder, 0.0, 0.0);
+    let last = *shape_builder.vertices().last().unwrap();
+    shape_builder.delete_line(0.0, 0.0, 1.0, 0.0);
+    shape_builder.delete_line(0.0, 0.0, 1.0, 1.0);
+    assert_eq!(shape_builder.vertices().len(), 3);
+    assert_eq!(shape_builder.vertices()[removed], last);
+    assert_ne!(last, (0.0, 0.0));
+
+    // Picking again gives the vertex's current ID
+    let pick = shape_builder.pick(1.1, 1.05, 0.2).unwrap();
+    assert_eq!(pick.id, vertex_at(&shape_builder, 1.0, 1.0));
+}
+
+// Test case: Away from the vertices the nearest edge is picked, with the cursor projected onto it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_pick_edge() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 4.0, 4.0, 0.0).unwrap();
+
+    let pick = shape_builder.pick(2.0, 4.3, 0.5).unwrap();
+    let top = shape_builder.lines().iter().position(|line| *line == vec![(0.0, 4.0), (4.0, 4.0)]).unwrap();
+    assert_eq!(pick.kind, PickKind::Edge);
+    assert_eq!(pick.id, top);
+    assert!((pick.distance - 0.3).abs() < 1e-12);
+    assert_eq!((pick.x, pick.y), (2.0, 4.0));
+
+    assert_eq!(shape_builder.pick(2.0, 2.0, 0.5), None);
+}
+
+// Test case: Vertices win over edges within the radius, but pick_edge only looks at edges
+#[wasm_bindgen_test(unsupported = test)]
+fn test_vertex_before_edge() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 4.0, 0.0).unwrap();
+
+    let pick = shape_builder.pick(0.3, 0.1, 0.5).unwrap();
+    assert_eq!(pick, Pick { kind: PickKind::Vertex, id: 0, distance: 0.3f64.hypot(0.1), x: 0.0, y: 0.0 });
+
+    let pick = shape_builder.pick_edge(0.3, 0.1, 0.5).unwrap();
+    assert_eq!((pick.kind, pick.id, pick.x, pick.y), (PickKind::Edge, 0, 0.3, 0.0));
+}
+
+// Test case: Picked edges can be deleted by ID, and unknown IDs are rejected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_delete_line_by_id() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(5.0, 0.0, 5.0, 1.0).unwrap();
+
+    let pick = shape_builder.pick_edge(5.1, 0.5, 0.2).unwrap();
+    shape_builder.delete_line_by_id(pick.id).unwrap();
+    assert_eq!(shape_builder.lines(), &[vec![(0.0, 0.0), (1.0, 0.0)]]);
+
+    assert_eq!(shape_builder.delete_line_by_id(1), Err(ShapeError::InvalidId));
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 2);
+}
@@ -0,0 +1,97 @@
+// Tests for adding polylines and polygons from a list of points
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A closed L-shape from its corners, added in one undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_closed_polygon() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_polygon(&[0.0, 0.0, 4.0, 0.0, 4.0, 1.0, 1.0, 1.0, 1.0, 3.0, 0.0, 3.0], true).unwrap();
+    assert_eq!(shape_builder.lines().len(), 6);
+    let section = shape_builder.section_properties().unwrap();
+    assert!(close(section.area, 6.0, 1e-9));
+    // The flange (4 x 1) and the rest of the leg (1 x 2)
+    assert!(close(section.centroid.0, (4.0 * 2.0 + 2.0 * 0.5) / 6.0, 1e-9));
+    assert!(close(section.centroid.1, (4.0 * 0.5 + 2.0 * 2.0) / 6.0, 1e-9));
+
+    assert!(shape_builder.undo());
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: An open polyline stays open, and closing it onto a drawing's edges encloses a region
+#[wasm_bindgen_test(unsupported = test)]
+fn test_open_polyline() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_polygon(&[0.0, 0.0, 2.0, 0.0, 2.0, 2.0], false).unwrap();
+    assert_eq!(shape_builder.lines().len(), 2);
+    assert!(shape_builder.area().is_err());
+
+    shape_builder.add_polygon(&[2.0, 2.0, 0.0, 2.0, 0.0, 0.0], false).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 4.0, 1e-9));
+}
+
+// Test case: A self-crossing polygon is split where it crosses itself, and repeated or closing points are skipped
+#[wasm_bindgen_test(unsupported = test)]
+fn test_crossings_and_repeats() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_polygon(&[0.0, 0.0, 2.0, 2.0, 2.0, 0.0, 0.0, 2.0], true).unwrap();
+    assert_eq!(shape_builder.lines().len(), 6);
+    assert!(shape_builder.vertices().contains(&(1.0, 1.0)));
+    assert!(close(shape_builder.area().unwrap(), 2.0, 1e-9));
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_polygon(&[0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0], true).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(close(shape_builder.area().unwrap(), 1.0, 1e-9));
+}
+
+// Test case: A non-finite coordinate adds nothing
+#[wasm_bindgen_test(unsupported = test)]
+fn test_polygon_nan_input() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.add_polygon(&[0.0, 0.0, 1.0, f64::NAN, 2.0, 2.0], true), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: An odd number of coordinates adds nothing
+#[wasm_bindgen_test(unsupported = test)]
+fn test_polygon_odd_coordinates() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.add_polygon(&[0.0, 0.0, 1.0, 1.0, 2.0], false), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Too few distinct points add nothing: a closed polygon needs three, a polyline two
+#[wasm_bindgen_test(unsupported = test)]
+fn test_polygon_too_few_points() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.add_polygon(&[5.0, 5.0, 6.0, 6.0], true), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.add_polygon(&[5.0, 5.0, 5.0, 5.0], false), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.add_polygon(&[], false), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Inside an open batch, a bad point list spoils the batch
+#[wasm_bindgen_test(unsupported = test)]
+fn test_polygon_spoils_batch() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    shape_builder.begin_batch();
+    shape_builder.add_polygon(&[5.0, 5.0, 6.0, 5.0, 6.0, 6.0], true).unwrap();
+    assert!(shape_builder.add_polygon(&[1.0], false).is_err());
+    assert!(shape_builder.commit_batch().is_err());
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
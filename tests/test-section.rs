@@ -0,0 +1,137 @@
+// Tests for the section properties of the enclosed area
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError; // Errors returned by ShapeBuilder
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A 4 x 2 rectangle has bh³/12 about x and hb³/12 about y, with the major axis vertical
+#[wasm_bindgen_test(unsupported = test)]
+fn test_rectangle_section() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(1.0, 3.0, 5.0, 1.0).unwrap();
+
+    let section = shape_builder.section_properties().unwrap();
+    assert!(close(section.area, 8.0, 1e-9));
+    assert_eq!(section.centroid, (3.0, 2.0));
+    assert!(close(section.ixx, 8.0 / 3.0, 1e-9));
+    assert!(close(section.iyy, 32.0 / 3.0, 1e-9));
+    assert!(close(section.ixy, 0.0, 1e-9));
+    assert!(close(section.i_major, 32.0 / 3.0, 1e-9));
+    assert!(close(section.i_minor, 8.0 / 3.0, 1e-9));
+    assert!(close(section.principal_angle, std::f64::consts::FRAC_PI_2, 1e-9));
+    assert!(close(section.polar(), 40.0 / 3.0, 1e-9));
+    assert!(section.to_string().contains("Area:"));
+}
+
+// Test case: A right triangle has a product of area, and principal axes at 45 degrees
+#[wasm_bindgen_test(unsupported = test)]
+fn test_triangle_section() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(3.0, 0.0, 0.0, 3.0).unwrap();
+    shape_builder.add_line(0.0, 3.0, 0.0, 0.0).unwrap();
+
+    let section = shape_builder.section_properties().unwrap();
+    assert!(close(section.area, 4.5, 1e-9));
+    assert!(close(section.ixx, 2.25, 1e-9)); // bh³/36
+    assert!(close(section.iyy, 2.25, 1e-9));
+    assert!(close(section.ixy, -1.125, 1e-9)); // -b²h²/72
+    assert!(close(section.i_major, 3.375, 1e-9));
+    assert!(close(section.i_minor, 1.125, 1e-9));
+    assert!(close(section.principal_angle, std::f64::consts::FRAC_PI_4, 1e-9));
+}
+
+// Test case: Holes subtract their second moments too
+#[wasm_bindgen_test(unsupported = test)]
+fn test_section_with_hole() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 4.0, 4.0, 0.0).unwrap();
+    shape_builder.add_rect(1.0, 3.0, 3.0, 1.0).unwrap();
+
+    // Square tube: (4⁴ - 2⁴) / 12 about both axes
+    let section = shape_builder.section_properties().unwrap();
+    assert!(close(section.area, 12.0, 1e-9));
+    assert!(close(section.ixx, 20.0, 1e-9));
+    assert!(close(section.iyy, 20.0, 1e-9));
+    assert_eq!(shape_builder.area(), Ok(12.0));
+    assert_eq!(shape_builder.loops().unwrap().iter().filter(|found| found.is_hole()).count(), 1);
+}
+
+// Test case: Properties about the centroid don't depend on where the section is drawn
+#[wasm_bindgen_test(unsupported = test)]
+fn test_section_ignores_position() {
+    let mut near = ShapeBuilder::new();
+    near.add_rect(0.0, 3.0, 2.0, 0.0).unwrap();
+    let mut far = ShapeBuilder::new();
+    far.add_rect(-500.0, 203.0, -498.0, 200.0).unwrap();
+
+    let (near, far) = (near.section_properties().unwrap(), far.section_properties().unwrap());
+    assert_eq!(far.centroid, (-499.0, 201.5));
+    assert!(close(far.ixx, near.ixx, 1e-9));
+    assert!(close(far.iyy, near.iyy, 1e-9));
+    assert!(close(far.ixy, near.ixy, 1e-9));
+}
+
+// Test case: An equal-leg angle (L section) matches the parallel axis theorem applied to its two rectangles
+#[wasm_bindgen_test(unsupported = test)]
+fn test_angle_section() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_polygon(&[0.0, 0.0, 4.0, 0.0, 4.0, 1.0, 1.0, 1.0, 1.0, 4.0, 0.0, 4.0], true).unwrap();
+
+    // Legs 4 x 1 and 1 x 3, centroids (2, 0.5) and (0.5, 2.5), section centroid (9.5/7, 9.5/7)
+    let section = shape_builder.section_properties().unwrap();
+    let c = 9.5 / 7.0;
+    assert!(close(section.area, 7.0, 1e-9));
+    assert!(close(section.centroid.0, c, 1e-9));
+    assert!(close(section.centroid.1, c, 1e-9));
+    let ixx = 4.0 / 12.0 + 4.0 * (0.5 - c).powi(2) + 9.0 / 4.0 + 3.0 * (2.5 - c).powi(2);
+    let ixy = 4.0 * (2.0 - c) * (0.5 - c) + 3.0 * (0.5 - c) * (2.5 - c);
+    assert!(close(section.ixx, ixx, 1e-9));
+    assert!(close(section.iyy, ixx, 1e-9));
+    assert!(close(section.ixy, ixy, 1e-9));
+    // Symmetric about y = x, so the principal axes are along and across the diagonal, the major one along it
+    assert!(close(section.i_major, ixx - ixy, 1e-9));
+    assert!(close(section.i_minor, ixx + ixy, 1e-9));
+    assert!(close(section.principal_angle, std::f64::consts::FRAC_PI_4, 1e-9));
+}
+
+// Test case: Rotating a rectangle turns its principal axes with it and leaves the principal moments unchanged. The major
+// axis is across the long side, so at 30 + 90 degrees, given as -60.
+#[wasm_bindgen_test(unsupported = test)]
+fn test_rotated_rectangle_principal_axes() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(-2.0, 0.5, 2.0, -0.5).unwrap();
+    shape_builder.rotate(Vec::new(), 30.0, 0.0, 0.0).unwrap();
+
+    let section = shape_builder.section_properties().unwrap();
+    assert!((section.i_major - 16.0 / 3.0).abs() < 1e-9);
+    assert!((section.i_minor - 1.0 / 3.0).abs() < 1e-9);
+    assert!((section.principal_angle - (-60f64).to_radians()).abs() < 1e-9);
+    assert!((section.polar() - 17.0 / 3.0).abs() < 1e-9);
+}
+
+// Test case: Radii of gyration are sqrt(I / A) about each axis
+#[wasm_bindgen_test(unsupported = test)]
+fn test_radii_of_gyration() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 6.0, 2.0, 0.0).unwrap();
+
+    let (rx, ry) = shape_builder.section_properties().unwrap().radii_of_gyration();
+    assert!(close(rx, 3.0f64.sqrt(), 1e-9));
+    assert!(close(ry, (1.0f64 / 3.0).sqrt(), 1e-9));
+}
+
+// Test case: A drawing that doesn't enclose anything has no section properties
+#[wasm_bindgen_test(unsupported = test)]
+fn test_section_needs_closed_area() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.section_properties(), Err(ShapeError::Degenerate));
+    shape_builder.add_line(0.0, 0.0, 1.0, 0.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.add_line(1.0, 1.0, 0.0, 2.0).unwrap();
+    assert_eq!(shape_builder.section_properties(), Err(ShapeError::NotClosed));
+}
@@ -0,0 +1,185 @@
+// Tests for importing and exporting SVG files
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: A rect, a polygon and a path with relative and shorthand commands give the expected closed shapes
+#[wasm_bindgen_test(unsupported = test)]
+fn test_straight_shapes() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg xmlns="http://www.w3.org/2000/svg"><rect x="0" y="0" width="4px" height="2"/></svg>"#, 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(close(shape_builder.area().unwrap(), 8.0, 1e-9));
+    assert_eq!(shape_builder.centroid().unwrap(), (2.0, 1.0));
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg><polygon points="0,0 6,0 0,6"/></svg>"#, 0.01).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 18.0, 1e-9));
+
+    // The same triangle drawn with m, h, l and z, numbers run together the way editors write them
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg><path d="m0 0h6l-6 6z"/></svg>"#, 0.01).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 18.0, 1e-9));
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 2.0, 1e-9) && close(y, 2.0, 1e-9));
+}
+
+// Test case: Circles, ellipses and arcs are flattened within the tolerance, so their area comes out close to the true one
+#[wasm_bindgen_test(unsupported = test)]
+fn test_curves() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg><circle cx="10" cy="10" r="5"/></svg>"#, 0.001).unwrap();
+    let exact = std::f64::consts::PI * 25.0;
+    let area = shape_builder.area().unwrap();
+    // Chords only cut the circle, and none by more than the tolerance
+    assert!(area < exact && area > std::f64::consts::PI * 4.999 * 4.999);
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 10.0, 1e-9) && close(y, 10.0, 1e-9));
+
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg><ellipse cx="0" cy="0" rx="4" ry="2"/></svg>"#, 0.001).unwrap();
+    assert!(close(shape_builder.area().unwrap(), std::f64::consts::PI * 8.0, 0.02));
+
+    // Half of a circle of radius 2, drawn as an arc and closed by its diameter
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg><path d="M -2 0 A 2 2 0 0 1 2 0 Z"/></svg>"#, 0.0001).unwrap();
+    assert!(close(shape_builder.area().unwrap(), std::f64::consts::PI * 2.0, 0.01));
+    let (_, y) = shape_builder.centroid().unwrap();
+    // With y pointing down, a clockwise sweep from the left end goes over the top, above the diameter
+    assert!(close(y, -8.0 / (3.0 * std::f64::consts::PI), 0.01));
+
+    // A quadratic and a cubic curve each end exactly on their end points
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.import_svg(r#"<svg><path d="M0 0 Q 5 5 10 0 C 10 -5 0 -5 0 0"/></svg>"#, 0.01).unwrap();
+    let vertices = shape_builder.vertices();
+    assert!(vertices.contains(&(10.0, 0.0)));
+    assert!(shape_builder.area().unwrap() > 0.0);
+}
+
+// Test case: Transforms on elements and on the groups around them are combined
+#[wasm_bindgen_test(unsupported = test)]
+fn test_transforms() {
+    let mut shape_builder = ShapeBuilder::new();
+    let svg = r#"<svg>
+        <g transform="translate(10, 20)">
+            <g transform="scale(2)">
+                <rect width="1" height="1" transform="rotate(90)"/>
+            </g>
+        </g>
+        <defs><rect width="100" height="100"/></defs>
+    </svg>"#;
+    shape_builder.import_svg(svg, 0.01).unwrap();
+    // The unit square rotated to x in [-1, 0], scaled by 2 and moved, leaving out the one in defs
+    assert!(close(shape_builder.area().unwrap(), 4.0, 1e-9));
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(close(x, 9.0, 1e-9) && close(y, 21.0, 1e-9));
+}
+
+// Test case: Imported outlines that cross are split like any other lines, and the import is a single undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_intersections_and_undo() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(-5.0, 1.0, 5.0, 1.0).unwrap();
+    shape_builder.import_svg(r#"<svg><polyline points="0,0 0,2"/></svg>"#, 0.01).unwrap();
+    assert_eq!(shape_builder.lines().len(), 4);
+
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines(), &[vec![(-5.0, 1.0), (5.0, 1.0)]]);
+}
+
+// Test case: Bad path data is reported at the line of its element, and nothing is added
+#[wasm_bindgen_test(unsupported = test)]
+fn test_bad_path_data() {
+    let mut shape_builder = ShapeBuilder::new();
+    let svg = "<svg>\n<rect width=\"1\" height=\"1\"/>\n<path d=\"M 0 0 L 1 x\"/>\n</svg>";
+    match shape_builder.import_svg(svg, 0.01) {
+        Err(ShapeError::Parse { line, .. }) => assert_eq!(line, Some(3)),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+    assert!(shape_builder.lines().is_empty());
+    assert!(!shape_builder.can_undo());
+}
+
+// Test case: A path that doesn't start with a move is refused
+#[wasm_bindgen_test(unsupported = test)]
+fn test_path_without_move() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_svg(r#"<svg><path d="L 1 1"/></svg>"#, 0.01), Err(ShapeError::Parse { .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Malformed XML is refused
+#[wasm_bindgen_test(unsupported = test)]
+fn test_malformed_xml() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_svg("<svg><rect", 0.01), Err(ShapeError::Parse { .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: A transform with ")" before its "(" is refused instead of panicking
+#[wasm_bindgen_test(unsupported = test)]
+fn test_malformed_transform() {
+    let mut shape_builder = ShapeBuilder::new();
+    let svg = r#"<svg><rect width="1" height="1" transform=")scale(2)"/></svg>"#;
+    assert!(matches!(shape_builder.import_svg(svg, 0.01), Err(ShapeError::Parse { line: Some(1), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Lengths in units other than px are refused at their line
+#[wasm_bindgen_test(unsupported = test)]
+fn test_unsupported_units() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert!(matches!(shape_builder.import_svg(r#"<svg><rect width="50%" height="1"/></svg>"#, 0.01), Err(ShapeError::Parse { line: Some(1), .. })));
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: A tolerance that isn't a positive number is refused
+#[wasm_bindgen_test(unsupported = test)]
+fn test_bad_tolerance() {
+    let mut shape_builder = ShapeBuilder::new();
+    assert_eq!(shape_builder.import_svg("<svg/>", 0.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.import_svg("<svg/>", f64::NAN), Err(ShapeError::NaNInput));
+}
+
+// Test case: An exported drawing imports again as the same lines
+#[wasm_bindgen_test(unsupported = test)]
+fn test_export_round_trip() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 3.0).unwrap();
+    shape_builder.add_line(0.5, 0.1, 3.25, 2.75).unwrap();
+    let svg = shape_builder.to_svg();
+
+    let mut imported = ShapeBuilder::new();
+    imported.import_svg(&svg, 0.01).unwrap();
+    assert_eq!(imported.lines(), shape_builder.lines());
+}
+
+// Test case: Regions are filled with holes left empty, and the centroid, axes and dimensions are labelled
+#[wasm_bindgen_test(unsupported = test)]
+fn test_export_annotations() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 10.0, 4.0).unwrap();
+    shape_builder.add_rect(1.0, 1.0, 3.0, 3.0).unwrap();
+    shape_builder.set_units("mm");
+    let svg = shape_builder.to_svg();
+
+    assert!(svg.contains("fill-rule=\"evenodd\""));
+    let regions = svg.lines().find(|line| line.contains("class=\"regions\"")).unwrap();
+    assert_eq!(regions.matches('Z').count(), 2);
+    let (x, y) = shape_builder.centroid().unwrap();
+    assert!(svg.contains(&format!("C ({:.3}, {}) mm", x, y)));
+    assert!(svg.contains("major axis, 90°") && svg.contains("minor axis, 0°"));
+    assert!(svg.contains(">10 mm</text>") && svg.contains(">4 mm</text>"));
+
+    // Lines that don't enclose anything are drawn without fill, centroid or axes
+    let mut open = ShapeBuilder::new();
+    open.add_line(0.0, 0.0, 1.0, 1.0).unwrap();
+    let svg = open.to_svg();
+    assert!(svg.contains("<line x1=\"0\" y1=\"0\" x2=\"1\" y2=\"1\"/>"));
+    assert!(!svg.contains("regions") && !svg.contains("centroid") && !svg.contains("axis"));
+}
@@ -0,0 +1,148 @@
+// Tests for translating, rotating, scaling and mirroring the drawing or selected edges
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::close;
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Transforming the whole drawing moves the centroid and turns the second moments as expected
+#[wasm_bindgen_test(unsupported = test)]
+fn test_whole_drawing() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 2.0).unwrap();
+    let before = shape_builder.section_properties().unwrap();
+
+    shape_builder.translate(vec![], 1.0, -3.0).unwrap();
+    assert_eq!(shape_builder.centroid().unwrap(), (3.0, -2.0));
+
+    // A quarter turn is exact, and swaps Ixx and Iyy
+    shape_builder.rotate(vec![], 90.0, 3.0, -2.0).unwrap();
+    let turned = shape_builder.section_properties().unwrap();
+    assert_eq!(turned.centroid, (3.0, -2.0));
+    assert!(close(turned.ixx, before.iyy, 1e-9) && close(turned.iyy, before.ixx, 1e-9));
+    assert!(shape_builder.lines().iter().flatten().all(|&(x, y)| [2.0, 4.0].contains(&x) && [-4.0, 0.0].contains(&y)));
+
+    // Any other angle keeps the area and principal moments and turns the principal axes with it
+    shape_builder.undo();
+    shape_builder.rotate(vec![], 30.0, 0.0, 0.0).unwrap();
+    let turned = shape_builder.section_properties().unwrap();
+    assert!(close(turned.area, 8.0, 1e-9));
+    assert!(close(turned.i_major, before.i_major, 1e-9) && close(turned.i_minor, before.i_minor, 1e-9));
+    assert!(close(turned.principal_angle, before.principal_angle + 30f64.to_radians() - std::f64::consts::PI, 1e-9));
+    let (x, y) = turned.centroid;
+    let angle = 30f64.to_radians();
+    assert!(close(x, 3.0 * angle.cos() + 2.0 * angle.sin(), 1e-9) && close(y, 3.0 * angle.sin() - 2.0 * angle.cos(), 1e-9));
+    shape_builder.undo();
+    shape_builder.undo();
+
+    // Non-uniform scale about a corner, then a mirror in the line y = x
+    shape_builder.scale(vec![], 2.0, 3.0, 0.0, 0.0).unwrap();
+    assert_eq!(shape_builder.area().unwrap(), 48.0);
+    assert_eq!(shape_builder.centroid().unwrap(), (4.0, 3.0));
+    shape_builder.mirror(vec![], 0.0, 0.0, 1.0, 1.0).unwrap();
+    assert_eq!(shape_builder.centroid().unwrap(), (3.0, 4.0));
+    assert_eq!(shape_builder.lines().len(), 4);
+}
+
+// Test case: Moving selected edges onto other geometry splits them where they cross, in one undo step
+#[wasm_bindgen_test(unsupported = test)]
+fn test_selected_edges() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_rect(10.0, 0.0, 12.0, 2.0).unwrap();
+    let selected = shape_builder.lines_in_box(9.0, -1.0, 13.0, 3.0);
+    assert_eq!(selected.len(), 4);
+
+    shape_builder.translate(selected, -7.0, 1.0).unwrap();
+    let mut expected = ShapeBuilder::new();
+    expected.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    expected.add_rect(3.0, 1.0, 5.0, 3.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), expected.lines().len());
+    assert!(close(shape_builder.area().unwrap(), 18.0, 1e-9));
+    assert_eq!(shape_builder.centroid().unwrap(), expected.centroid().unwrap());
+
+    assert!(shape_builder.undo());
+    assert!(close(shape_builder.area().unwrap(), 20.0, 1e-9));
+    assert!(shape_builder.lines().iter().flatten().any(|&point| point == (12.0, 2.0)));
+
+    // Only the selected edge moves; the square it came from is left open
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 2.0, 2.0).unwrap();
+    let (top, _) = shape_builder.nearest_line(1.0, 2.0, 0.1).unwrap();
+    shape_builder.mirror(vec![top, top], 0.0, 3.0, 1.0, 3.0).unwrap();
+    assert!(shape_builder.lines().iter().any(|line| line[0].1 == 4.0 && line[1].1 == 4.0));
+    assert_eq!(shape_builder.area(), Err(ShapeError::NotClosed));
+}
+
+// Test case: Moving the whole drawing moves the part labels with it, undo and redo included, while moving some edges
+// leaves them where they are
+#[wasm_bindgen_test(unsupported = test)]
+fn test_part_labels() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_rect(10.0, 0.0, 12.0, 2.0).unwrap();
+    shape_builder.add_part("web", 2.0, 2.0).unwrap();
+
+    shape_builder.translate(vec![], 5.0, -1.0).unwrap();
+    assert_eq!(shape_builder.parts()[0].at, (7.0, 1.0));
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.parts()[0].at, (2.0, 2.0));
+    assert!(shape_builder.redo());
+    assert_eq!(shape_builder.parts()[0].at, (7.0, 1.0));
+
+    shape_builder.rotate(vec![], 90.0, 0.0, 0.0).unwrap();
+    assert_eq!(shape_builder.parts()[0].at, (-1.0, 7.0));
+
+    let selected = shape_builder.lines_in_box(-3.0, 14.0, 2.0, 18.0);
+    assert_eq!(selected.len(), 4);
+    shape_builder.translate(selected, 0.0, 10.0).unwrap();
+    assert_eq!(shape_builder.parts()[0].at, (-1.0, 7.0));
+}
+
+// Test case: An unknown edge ID fails without changing anything
+#[wasm_bindgen_test(unsupported = test)]
+fn test_transform_invalid_id() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let version = shape_builder.version();
+    assert_eq!(shape_builder.translate(vec![0, 4], 1.0, 1.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.version(), version);
+}
+
+// Test case: Non-finite arguments, and results past the largest float, fail without changing anything
+#[wasm_bindgen_test(unsupported = test)]
+fn test_transform_nan_input() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let version = shape_builder.version();
+    assert_eq!(shape_builder.rotate(vec![], f64::NAN, 0.0, 0.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.scale(vec![], 1e308, 1e308, -1.0, 0.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.version(), version);
+}
+
+// Test case: A zero scale factor, or a mirror line through a single point, is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_transform_degenerate() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    assert_eq!(shape_builder.scale(vec![], 0.0, 1.0, 0.0, 0.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.mirror(vec![], 1.0, 1.0, 1.0, 1.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Inside a batch, a failed transform spoils the batch like any other edit
+#[wasm_bindgen_test(unsupported = test)]
+fn test_transform_spoils_batch() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 1.0, 1.0).unwrap();
+    let lines = shape_builder.lines().to_vec();
+    shape_builder.begin_batch();
+    shape_builder.add_line(5.0, 5.0, 6.0, 6.0).unwrap();
+    assert!(shape_builder.translate(vec![9], 1.0, 0.0).is_err());
+    assert_eq!(shape_builder.commit_batch(), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
@@ -0,0 +1,164 @@
+// Tests for trimming, extending and splitting edges
+use wasm_bindgen_test::wasm_bindgen_test;
+use centroid_calculator::ShapeBuilder; // ShapeBuilder is responsible for managing shapes
+use centroid_calculator::ShapeError;
+mod common;
+use common::{close, edge_at};
+
+// Configures the testing utility to run the tests in a browser environment
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+// Test case: Trimming the stubs where two sides overshoot a corner leaves a closed rectangle
+#[wasm_bindgen_test(unsupported = test)]
+fn test_trim_overshoot() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 4.0, 0.0).unwrap();
+    shape_builder.add_line(4.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_line(5.0, 3.0, 0.0, 3.0).unwrap();
+    shape_builder.add_line(0.0, 3.0, 0.0, 0.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 6);
+
+    // The edge picks the line and the point picks the part of it, so the stub can be trimmed through the long piece
+    let top = edge_at(&shape_builder, 2.0, 3.0);
+    shape_builder.trim(top, 4.5, 3.0).unwrap();
+    let stub = edge_at(&shape_builder, 4.0, 3.5);
+    shape_builder.trim(stub, 4.0, 3.9).unwrap();
+
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(close(shape_builder.area().unwrap(), 12.0, 1e-9));
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 5);
+}
+
+// Test case: Trimming between the lines a line crosses removes the whole span, including edges split off within it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_trim_between_cuts() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    shape_builder.add_line(-1.0, 2.0, 5.0, 2.0).unwrap();
+    let middle = edge_at(&shape_builder, 2.0, 2.0);
+    shape_builder.split_edge(middle, 0.25).unwrap();
+    assert_eq!(shape_builder.lines().len(), 10);
+
+    let left_stub = edge_at(&shape_builder, -0.5, 2.0);
+    shape_builder.trim(left_stub, 3.0, 2.0).unwrap();
+    assert_eq!(shape_builder.lines().len(), 8);
+    assert!(shape_builder.pick_edge(2.0, 2.0, 0.01).is_none());
+    assert!(shape_builder.pick_edge(-0.5, 2.0, 0.01).is_some() && shape_builder.pick_edge(4.5, 2.0, 0.01).is_some());
+    assert!(close(shape_builder.area().unwrap(), 16.0, 1e-9));
+
+    // A line with nothing crossing it is removed whole
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 1.0, 1.0).unwrap();
+    shape_builder.trim(0, 0.2, 0.2).unwrap();
+    assert!(shape_builder.lines().is_empty());
+}
+
+// Test case: Extending lines to meet others closes a drawing, splitting the boundary where the extension meets it
+#[wasm_bindgen_test(unsupported = test)]
+fn test_extend() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(1.0, 0.0, 6.0, 0.0).unwrap();
+    shape_builder.add_line(6.0, 0.0, 0.0, 3.0).unwrap();
+    shape_builder.add_line(1.0, 0.0, 1.0, 1.0).unwrap();
+    let left = edge_at(&shape_builder, 1.0, 0.5);
+    let diagonal = edge_at(&shape_builder, 3.0, 1.5);
+    shape_builder.extend(left, diagonal).unwrap();
+
+    assert_eq!(shape_builder.lines().len(), 4);
+    assert!(shape_builder.vertices().contains(&(1.0, 2.5)));
+    assert!(close(shape_builder.area().unwrap(), 0.5 * 5.0 * 2.5, 1e-9));
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 3);
+
+    // The end nearer the boundary is the one extended
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(1.0, 0.0, 3.0, 0.0).unwrap();
+    shape_builder.add_line(0.0, -1.0, 0.0, 1.0).unwrap();
+    shape_builder.extend(0, 1).unwrap();
+    assert!(shape_builder.lines().iter().any(|line| line[..] == [(0.0, 0.0), (3.0, 0.0)] || line[..] == [(3.0, 0.0), (0.0, 0.0)]));
+}
+
+// Test case: Splitting an edge adds a vertex that can be dragged, and stays split through other edits
+#[wasm_bindgen_test(unsupported = test)]
+fn test_split_edge() {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_rect(0.0, 0.0, 4.0, 4.0).unwrap();
+    let top = edge_at(&shape_builder, 2.0, 4.0);
+    shape_builder.split_edge(top, 0.5).unwrap();
+    assert_eq!(shape_builder.lines().len(), 5);
+    assert!(close(shape_builder.area().unwrap(), 16.0, 1e-9));
+
+    let middle = shape_builder.vertices().iter().position(|&point| point == (2.0, 4.0)).unwrap();
+    shape_builder.move_vertex(middle, 2.0, 6.0).unwrap();
+    assert!(close(shape_builder.area().unwrap(), 20.0, 1e-9));
+
+    assert!(shape_builder.undo());
+    assert!(shape_builder.undo());
+    assert_eq!(shape_builder.lines().len(), 4);
+}
+
+// Two parallel lines, a short line below and right of them, and a line crossing both
+fn trim_fixture() -> ShapeBuilder {
+    let mut shape_builder = ShapeBuilder::new();
+    shape_builder.add_line(0.0, 0.0, 2.0, 0.0).unwrap();
+    shape_builder.add_line(0.0, 1.0, 2.0, 1.0).unwrap();
+    shape_builder.add_line(5.0, -1.0, 5.0, 0.5).unwrap();
+    shape_builder.add_line(1.0, -1.0, 1.0, 2.0).unwrap();
+    shape_builder
+}
+
+// Test case: Unknown edge IDs fail without changing anything
+#[wasm_bindgen_test(unsupported = test)]
+fn test_trim_invalid_id() {
+    let mut shape_builder = trim_fixture();
+    let lines = shape_builder.lines().to_vec();
+    let bottom = edge_at(&shape_builder, 0.5, 0.0);
+    assert_eq!(shape_builder.trim(99, 0.0, 0.0), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.extend(99, bottom), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.extend(bottom, 99), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.split_edge(99, 0.5), Err(ShapeError::InvalidId));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: Non-finite points and positions fail without changing anything
+#[wasm_bindgen_test(unsupported = test)]
+fn test_trim_nan_input() {
+    let mut shape_builder = trim_fixture();
+    let lines = shape_builder.lines().to_vec();
+    let bottom = edge_at(&shape_builder, 0.5, 0.0);
+    assert_eq!(shape_builder.trim(bottom, f64::NAN, 0.0), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.split_edge(bottom, f64::INFINITY), Err(ShapeError::NaNInput));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
+
+// Test case: A line can't be extended to a boundary it's parallel to, would pass beside, already crosses, or is
+#[wasm_bindgen_test(unsupported = test)]
+fn test_extend_no_intersection() {
+    let mut shape_builder = trim_fixture();
+    let lines = shape_builder.lines().to_vec();
+    let bottom = edge_at(&shape_builder, 0.5, 0.0);
+    let top = edge_at(&shape_builder, 0.5, 1.0);
+    let short = edge_at(&shape_builder, 5.0, 0.0);
+    let crossing = edge_at(&shape_builder, 1.0, 1.5);
+    assert_eq!(shape_builder.extend(bottom, top), Err(ShapeError::NoIntersection));
+    assert_eq!(shape_builder.extend(top, short), Err(ShapeError::NoIntersection));
+    assert_eq!(shape_builder.extend(bottom, crossing), Err(ShapeError::NoIntersection));
+    assert_eq!(shape_builder.extend(bottom, bottom), Err(ShapeError::NoIntersection));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+
+    // The short line does reach the bottom line's extension
+    shape_builder.extend(bottom, short).unwrap();
+    assert!(shape_builder.vertices().contains(&(5.0, 0.0)));
+}
+
+// Test case: Splitting an edge at or beyond its ends is degenerate
+#[wasm_bindgen_test(unsupported = test)]
+fn test_split_edge_outside() {
+    let mut shape_builder = trim_fixture();
+    let lines = shape_builder.lines().to_vec();
+    let bottom = edge_at(&shape_builder, 0.5, 0.0);
+    assert_eq!(shape_builder.split_edge(bottom, 0.0), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.split_edge(bottom, 1.5), Err(ShapeError::Degenerate));
+    assert_eq!(shape_builder.lines(), &lines[..]);
+}
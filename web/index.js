@@ -1,4 +1,7 @@
-import init, { ShapeBuilder} from './dist/centroid_calculator.js';
+import init, { ShapeBuilder, PickKind } from './dist/centroid_calculator.js';
+
+// How close, in pixels, the cursor has to be to a vertex or line to select it
+const PICK_RADIUS = 6;
 
 function drawLine(line, color, ctx) {
   // Draw the line
@@ -100,38 +103,13 @@ function setActiveButton(buttonId) {
 }
 
 
-// Helper function that gets the distance squared from the cursor to any given line
-function distance2Line(ax, ay, bx, by, cx, cy) {
-  let [A,B,C,D] = [(cx - ax), (cy - ay), (bx - ax), (by - ay)];
-  let dot = A * C + B * D;
-  let len_sq = C * C + D * D;
-  let param = dot / len_sq;  
-  var xx, yy;
-  if (param < 0) {
-    xx = ax;
-    yy = ay;
-  } else if (param > 1) {
-    xx = bx;
-    yy = by;
-  } else {
-    xx = ax + param * C;
-    yy = ay + param * D;
-  }
-  let distance2 = (cx - xx)**2 + (cy - yy)**2;
-  return distance2;
-}
-
-// Helper function that gets the distance squared from the cursor to any given point
-function distance2Point(ax, ay, cx, cy) {
-  let distance2 = (cx - ax)**2 + (cy - ay)**2;
-  return distance2;
-}
-
 window.onload = async function() {
   await init(); // Initialize Wasm package
 
   const shapeBuilder = ShapeBuilder.new();
   let lines;
+  let storedLines = []; // lines as stored in shapeBuilder, before adjusting for grid offsets and zoom
+  let storedVersion = -1; // shapeBuilder.version() when storedLines was read
 
   let gridOffsetX = 0;
   let gridOffsetY = 0;
@@ -163,7 +141,43 @@ window.onload = async function() {
   
   let currentTool = 'select';
 
+  // Finds the vertex or line under the cursor with ShapeBuilder.pick, converting the cursor to stored coordinates first
+  function pickAt(e, edgesOnly) {
+    let [x, y] = storedPoint(e);
+    let pick = edgesOnly ? shapeBuilder.pick_edge(x, y, PICK_RADIUS / scale) : shapeBuilder.pick(x, y, PICK_RADIUS / scale);
+    if (!pick) return undefined;
+    let result = { kind: pick.kind, id: pick.id, x: pick.x, y: pick.y };
+    pick.free();
+    return result;
+  }
+
+  // The cursor in stored coordinates, undoing grid offsets and zoom
+  function storedPoint(e) {
+    return [(e.offsetX - gridOffsetX + 400 * (scale - 1)) / scale, (e.offsetY - gridOffsetY + 300 * (scale - 1)) / scale];
+  }
+
+  // Re-reads storedLines from shapeBuilder's packed vertex and edge buffers, but only if the lines changed since last time
+  function refreshLines() {
+    let version = shapeBuilder.version();
+    if (version === storedVersion) return;
+    let coordinates = shapeBuilder.vertex_buffer();
+    let edges = shapeBuilder.edge_buffer();
+    storedLines = [];
+    for (let i = 0; i < edges.length; i += 2) {
+      let [a, b] = [edges[i] * 2, edges[i + 1] * 2];
+      storedLines.push([[coordinates[a], coordinates[a + 1]], [coordinates[b], coordinates[b + 1]]]);
+    }
+    storedVersion = version;
+  }
+
+  // The stored lines, adjusted for grid offsets and zoom
+  function screenLines() {
+    refreshLines();
+    return storedLines.map(line => line.map(([x, y]) => [x + gridOffsetX - (400 - x) * (scale - 1), y + gridOffsetY - (300 - y) * (scale - 1)]));
+  }
+
   let startX, startY, isDrawing, gridShift = false;
+  let draggedVertex = null; // { id, x, y } of the vertex being dragged with the select tool (x, y stored), and where the mouse went down
   let rectStartX, rectStartY = false;
 
   //Draws the initial grid lines
@@ -214,16 +228,48 @@ window.onload = async function() {
       valid = false;
     }
     if (valid) {
-      shapeBuilder.add_line((point1[0] * 50) + 400, -(point1[1] * 50) + 300, (point2[0] * 50) + 400, -(point2[1] * 50) + 300);
-      document.getElementById("linePoint1").value = "";
-      document.getElementById("linePoint2").value = "";
+      try {
+        shapeBuilder.add_line((point1[0] * 50) + 400, -(point1[1] * 50) + 300, (point2[0] * 50) + 400, -(point2[1] * 50) + 300);
+        document.getElementById("linePoint1").value = "";
+        document.getElementById("linePoint2").value = "";
+      } catch (err) {
+        console.error("Line rejected (" + err.kind + "): " + err.message);
+      }
+    }
+
+    // Redraw all lines and shapes
+    drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
+    lines = screenLines();
+    lines.forEach(line => {
+      drawLine(line, 'black', ctx);
+    });
+  });
+
+  document.getElementById('drawNumPolygonButton').addEventListener('click', () => {
+    currentTool = 'drawLine';
+    console.log("Numerical Input Polyline Drawn");
+
+    // Set active class on selected tool and remove from others
+    setActiveButton('drawLineButton');
+    // Any list of numbers taken in pairs, e.g. "(0, 0) (4, 0) (4, 1)" or "0,0, 4,0, 4,1"
+    let numbers = (document.getElementById("polygonPoints").value.match(/[-+]?(\d+\.?\d*|\.\d+)(e[-+]?\d+)?/gi) || []).map(parseFloat);
+    if (numbers.length < 4 || numbers.length % 2 != 0 || numbers.some(isNaN)) {
+      document.getElementById("polygonPoints").value = "INVALID INPUT";
+    } else {
+      // Grid units to stored coordinates, as for single lines
+      let points = new Float64Array(numbers.map((value, i) => i % 2 == 0 ? (value * 50) + 400 : -(value * 50) + 300));
+      try {
+        shapeBuilder.add_polygon(points, document.getElementById("polygonClosed").checked);
+        document.getElementById("polygonPoints").value = "";
+      } catch (err) {
+        console.error("Polyline rejected (" + err.kind + "): " + err.message);
+      }
     }
 
     // Redraw all lines and shapes
     drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
-    lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
+    lines = screenLines();
     lines.forEach(line => {
-      [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
       drawLine(line, 'black', ctx);
     });
   });
@@ -264,16 +310,19 @@ window.onload = async function() {
       valid = false;
     }
     if (valid) {
-      shapeBuilder.add_rect((point1[0] * 50) + 400, -(point1[1] * 50) + 300, ((point1[0] + width) * 50) + 400, -((point1[1] - height) * 50) + 300);
-      document.getElementById("rectPoint1").value = "";
-      document.getElementById("rectW").value = "";
-      document.getElementById("rectH").value = "";
+      try {
+        shapeBuilder.add_rect((point1[0] * 50) + 400, -(point1[1] * 50) + 300, ((point1[0] + width) * 50) + 400, -((point1[1] - height) * 50) + 300);
+        document.getElementById("rectPoint1").value = "";
+        document.getElementById("rectW").value = "";
+        document.getElementById("rectH").value = "";
+      } catch (err) {
+        console.error("Rectangle rejected (" + err.kind + "): " + err.message);
+      }
     }
     // Redraw all lines and shapes
     drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
-    lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
+    lines = screenLines();
     lines.forEach(line => {
-      [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
       drawLine(line, 'black', ctx);
     });
   });
@@ -292,24 +341,10 @@ window.onload = async function() {
     // Set active class on selected tool button and remove from others
     setActiveButton('calculateCentroidButton');
 
-    // Calculate the centroid if a shape is closed
-    
-    // Check if the shape is closed 
-    let isClosed = false;
-
-    if (shapeBuilder.get_lines().length >= 3) {
-      const lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
-      const firstLine = lines[0];
-      const lastLine = lines[lines.length - 1];
-
-      // Check if the last point of the last line matches the first point of the first line 
-      if (firstLine[0][0] === lastLine[1][0] && firstLine[0][1] === lastLine[1][1]) {
-        isClosed = true;
-        console.log("Shape is closed, centroid calculated!");
-      }
-    }
-    const centroid = shapeBuilder.calculate_centroid();
-    if (centroid && centroid !== null) /* && (isClosed)) */ { // Detecting if a shape is closed is not working atm
+    // Calculate the centroid if a shape is closed. Failures are thrown as a ShapeError whose kind says what went wrong
+    // (NotClosed, Degenerate, NaNInput, ZeroArea or SelfIntersecting).
+    try {
+      const centroid = shapeBuilder.calculate_centroid();
       console.log('Centroid: ', centroid);
 
       // Draw the centroid on the canvas
@@ -320,9 +355,8 @@ window.onload = async function() {
       let xPos = (actualX0 - gridOffsetX - 400) / 50;
       let yPos = (actualY0 - gridOffsetY - 300) / -50;
       ctx.fillText("(" + xPos.toString() + ", " + yPos.toString() + ")", actualX0 + 11, actualY0 - 4);
-    }
-    else {
-      console.log("Centroid calculation failed or insufficient points.")
+    } catch (err) {
+      console.log("Centroid calculation failed (" + err.kind + "): " + err.message);
     }
   });
 
@@ -339,6 +373,38 @@ window.onload = async function() {
     console.log("Canvas Cleared");
   });
 
+  // Undo/redo step back and forth through whole user actions (a rectangle, a line and the splits it caused, a clear...)
+  function redrawAfterHistory(changed) {
+    if (!changed) return;
+    ctx.clearRect(0, 0, canvas.width, canvas.height);
+    drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
+    lines = screenLines();
+    lines.forEach(line => {
+      drawLine(line, 'black', ctx);
+    });
+  }
+
+  document.getElementById('undoButton').addEventListener('click', () => {
+    redrawAfterHistory(shapeBuilder.undo());
+  });
+
+  document.getElementById('redoButton').addEventListener('click', () => {
+    redrawAfterHistory(shapeBuilder.redo());
+  });
+
+  // Ctrl+Z to undo, Ctrl+Y or Ctrl+Shift+Z to redo (ignored while typing coordinates)
+  document.addEventListener('keydown', (e) => {
+    if (!(e.ctrlKey || e.metaKey) || e.target.tagName === 'INPUT') return;
+    const key = e.key.toLowerCase();
+    if (key === 'z' && !e.shiftKey) {
+      e.preventDefault();
+      redrawAfterHistory(shapeBuilder.undo());
+    } else if (key === 'y' || (key === 'z' && e.shiftKey)) {
+      e.preventDefault();
+      redrawAfterHistory(shapeBuilder.redo());
+    }
+  });
+
   // ================[ END TOOLBAR ]========================
 
   // ================[ MOUSE EVENTS ]=======================
@@ -346,9 +412,15 @@ window.onload = async function() {
 
   canvas.addEventListener('mousedown', function (e) {
     if (currentTool === 'select') {
-      startX = e.offsetX;
-      startY = e.offsetY;
-      gridShift = true;
+      // Pressing on a vertex drags it; anywhere else pans the grid
+      let pick = pickAt(e, false);
+      if (pick && pick.kind === PickKind.Vertex) {
+        draggedVertex = { id: pick.id, x: pick.x, y: pick.y, downX: e.offsetX, downY: e.offsetY };
+      } else {
+        startX = e.offsetX;
+        startY = e.offsetY;
+        gridShift = true;
+      }
     }
     if (currentTool === 'drawLine') {
       startX = e.offsetX;
@@ -364,14 +436,11 @@ window.onload = async function() {
     }
     
     if (currentTool === 'deleteLine') {
-      lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
-      lines.forEach(line => {
-        let distance2 = distance2Line(line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1), e.offsetX, e.offsetY);
-        if (distance2 <= 35) {
-          shapeBuilder.delete_line(line[0][0], line[0][1], line[1][0], line[1][1]);
-          console.log("Line deleted:", line[0][0], line[0][1], line[1][0], line[1][1]);
-        }
-      });
+      let pick = pickAt(e, true);
+      if (pick) {
+        shapeBuilder.delete_line_by_id(pick.id);
+        console.log("Line deleted:", pick.id);
+      }
     }
   });
 
@@ -388,9 +457,8 @@ window.onload = async function() {
     drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
     try {
       //adjusting for grid offsets
-      lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
+      lines = screenLines();
       lines.forEach(line => {
-        [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
         drawLine(line, 'black', ctx);
       });
       //console.log("Lines retrieved:", lines);
@@ -399,43 +467,37 @@ window.onload = async function() {
       lines = [];
     }
 
-    if (currentTool === 'select' && !gridShift) {
-      lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
-      let drawnCoords = [];
-      lines.forEach(line => {
-        let [actualX0, actualY0, actualX1, actualY1] = [line[0][0], line[0][1], line[1][0], line[1][1]];
-        [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
-        let distance2A = distance2Point(line[0][0], line[0][1], e.offsetX, e.offsetY);
-        let distance2B = distance2Point(line[1][0], line[1][1], e.offsetX, e.offsetY);
-        let distance2 = distance2Line(line[0][0], line[0][1], line[1][0], line[1][1], e.offsetX, e.offsetY);
-        if (distance2A <= 40) {
-          //highlights vertex and displays coordinates
-          drawVertex(line[0][0], line[0][1], 'yellow', ctx);
-          let xPos = (actualX0 - 400) / 50;
-          let yPos = (actualY0 - 300) / -50;
-          ctx.fillStyle = "black";
-          if (!drawnCoords.includes((xPos, yPos))) {
-            ctx.fillText("(" + xPos.toString() + ", " + yPos.toString() + ")", line[0][0] + 11, line[0][1] - 4);
-            drawnCoords.push((xPos, yPos));
+    if (currentTool === 'select' && draggedVertex) {
+      // Preview the edges meeting at the dragged vertex following the cursor; the drawing only changes on mouseup
+      storedLines.forEach((line, i) => {
+        line.forEach(([x, y], end) => {
+          if (x === draggedVertex.x && y === draggedVertex.y) {
+            let other = lines[i][1 - end];
+            drawLine([other, [endX, endY]], 'blue', ctx);
           }
-        } else if (distance2B <= 40) {
-          drawVertex(line[1][0], line[1][1], 'yellow', ctx);
-          let xPos = (actualX1 - 400) / 50;
-          let yPos = (actualY1 - 300) / -50;
-          ctx.fillStyle = "black";
-          if (!drawnCoords.includes((xPos, yPos))) {
-            ctx.fillText("(" + xPos.toString() + ", " + yPos.toString() + ")", line[1][0] + 11, line[1][1] - 4);
-            drawnCoords.push((xPos, yPos));
-          }
-        } else if (distance2 <= 35) {
-          //highlights line and displays length
-          let [xPos0, yPos0, xPos1, yPos1] = [(actualX0 - 400) / 50, (actualY0 - 300) / -50, (actualX1 - 400) / 50, (actualY1 - 300) / -50];
-          let actualDistance = ((xPos0 - xPos1)**2 + (yPos0 - yPos1)**2)**0.5;
-          ctx.fillStyle = "black";
-          ctx.fillText("len = " + actualDistance.toString(), e.offsetX + 5, e.offsetY - 5);
-          drawLine(line, 'yellow', ctx);
-        }
+        });
       });
+      drawVertex(endX, endY, 'yellow', ctx);
+    }
+    if (currentTool === 'select' && !gridShift && !draggedVertex) {
+      let pick = pickAt(e, false);
+      if (pick && pick.kind === PickKind.Vertex) {
+        //highlights vertex and displays coordinates
+        let [vertexX, vertexY] = [pick.x + gridOffsetX - (400 - pick.x) * (scale - 1), pick.y + gridOffsetY - (300 - pick.y) * (scale - 1)];
+        drawVertex(vertexX, vertexY, 'yellow', ctx);
+        let xPos = (pick.x - 400) / 50;
+        let yPos = (pick.y - 300) / -50;
+        ctx.fillStyle = "black";
+        ctx.fillText("(" + xPos.toString() + ", " + yPos.toString() + ")", vertexX + 11, vertexY - 4);
+      } else if (pick && lines[pick.id]) {
+        //highlights line and displays length
+        let line = storedLines[pick.id];
+        let [xPos0, yPos0, xPos1, yPos1] = [(line[0][0] - 400) / 50, (line[0][1] - 300) / -50, (line[1][0] - 400) / 50, (line[1][1] - 300) / -50];
+        let actualDistance = ((xPos0 - xPos1)**2 + (yPos0 - yPos1)**2)**0.5;
+        ctx.fillStyle = "black";
+        ctx.fillText("len = " + actualDistance.toString(), e.offsetX + 5, e.offsetY - 5);
+        drawLine(lines[pick.id], 'yellow', ctx);
+      }
     }
     if (currentTool === 'select' && gridShift) {
       let currentX = e.offsetX;
@@ -470,56 +532,63 @@ window.onload = async function() {
 
     if (currentTool === 'deleteLine') {
       // HIGHLIGHT A LINE IF HOVERING OVER IT
-      lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
-      lines.forEach(line => {
-        [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
-        let distance2 = distance2Line(line[0][0], line[0][1], line[1][0], line[1][1], e.offsetX, e.offsetY);
-        if (distance2 <= 35) {
-          drawLine(line, 'red', ctx)
-        }
-      });
+      let pick = pickAt(e, true);
+      if (pick && lines[pick.id]) {
+        drawLine(lines[pick.id], 'red', ctx)
+      }
     }
   }); 
 
   canvas.addEventListener('mouseup', function (e) {
-    if (!isDrawing && !gridShift) return; 
+    if (!isDrawing && !gridShift && !draggedVertex) return; 
 
     isDrawing = false;
     gridShift = false;
     const endX = e.offsetX;
     const endY = e.offsetY;
 
-    // Add the shape to the shape builder
-    if (currentTool === 'drawRect') {
-      // Ensure all coordinates are valid numbers
-      if (isNaN(rectStartX) || isNaN(rectStartY) || isNaN(endX) || isNaN(endY)) {
-        console.error("Invalid coordinates passed to add_rectangle:", rectStartX, rectStartY, endX, endY);
-        return;
-      }
-      shapeBuilder.add_rect(rectStartX - gridOffsetX + (400 + gridOffsetX - rectStartX) * ((scale - 1) / scale), rectStartY - gridOffsetY + (300 + gridOffsetY - rectStartY) * ((scale - 1) / scale), endX - gridOffsetX + (400 + gridOffsetX - endX) * ((scale - 1) / scale), endY - gridOffsetY + (300 + gridOffsetY - endY) * ((scale - 1) / scale));
-      console.log("Rectangle added to ShapeBuilder:", rectStartX, rectStartY, endX, endY);
-    } else if (currentTool === 'drawLine') {
-      if (isNaN(endX) || isNaN(endY)) {
-        console.error("Invalid coordinates passed to add_line:", startX, startY, endX, endY);
-        return;
+    // Add the shape to the shape builder. Clicking without dragging gives a degenerate shape, which is rejected
+    try {
+      if (currentTool === 'drawRect') {
+        // Ensure all coordinates are valid numbers
+        if (isNaN(rectStartX) || isNaN(rectStartY) || isNaN(endX) || isNaN(endY)) {
+          console.error("Invalid coordinates passed to add_rectangle:", rectStartX, rectStartY, endX, endY);
+          return;
+        }
+        shapeBuilder.add_rect(rectStartX - gridOffsetX + (400 + gridOffsetX - rectStartX) * ((scale - 1) / scale), rectStartY - gridOffsetY + (300 + gridOffsetY - rectStartY) * ((scale - 1) / scale), endX - gridOffsetX + (400 + gridOffsetX - endX) * ((scale - 1) / scale), endY - gridOffsetY + (300 + gridOffsetY - endY) * ((scale - 1) / scale));
+        console.log("Rectangle added to ShapeBuilder:", rectStartX, rectStartY, endX, endY);
+      } else if (draggedVertex) {
+        // A click without dragging leaves the vertex where it is
+        if (e.offsetX !== draggedVertex.downX || e.offsetY !== draggedVertex.downY) {
+          let [x, y] = storedPoint(e);
+          shapeBuilder.move_vertex(draggedVertex.id, x, y);
+          console.log("Vertex moved:", draggedVertex.id, x, y);
+        }
+      } else if (currentTool === 'drawLine') {
+        if (isNaN(endX) || isNaN(endY)) {
+          console.error("Invalid coordinates passed to add_line:", startX, startY, endX, endY);
+          return;
+        }
+        shapeBuilder.add_line(startX - gridOffsetX + (400 + gridOffsetX - startX) * ((scale - 1) / scale), startY - gridOffsetY + (300 + gridOffsetY - startY) * ((scale - 1) / scale), endX - gridOffsetX + (400 + gridOffsetX - endX) * ((scale - 1) / scale), endY - gridOffsetY + (300 + gridOffsetY - endY) * ((scale - 1) / scale));
+        console.log("Line added to ShapeBuilder:", startX, startY, endX, endY);
+      } else {
+        console.log("Invalid Shape.");
       }
-      shapeBuilder.add_line(startX - gridOffsetX + (400 + gridOffsetX - startX) * ((scale - 1) / scale), startY - gridOffsetY + (300 + gridOffsetY - startY) * ((scale - 1) / scale), endX - gridOffsetX + (400 + gridOffsetX - endX) * ((scale - 1) / scale), endY - gridOffsetY + (300 + gridOffsetY - endY) * ((scale - 1) / scale));
-      console.log("Line added to ShapeBuilder:", startX, startY, endX, endY);
-    } else {
-      console.log("Invalid Shape.");
+    } catch (err) {
+      console.log("Shape rejected (" + err.kind + "): " + err.message);
     }
 
     
 
     // Redraw all lines and shapes
     drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
-    lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
+    lines = screenLines();
     lines.forEach(line => {
-      [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
       drawLine(line, 'black', ctx);
     });
 
     // Reset Coordinates after drawing.
+    draggedVertex = null;
     rectStartX = null;
     rectStartY = null;
     startX = null;
@@ -557,9 +626,8 @@ window.onload = async function() {
       //redraw all the lines
       ctx.clearRect(0, 0, canvas.width, canvas.height);
       drawGrid(relativeScale, factor, gridOffsetX, gridOffsetY, ctx);
-      lines = JSON.parse(JSON.stringify(shapeBuilder.get_lines()));
+      lines = screenLines();
       lines.forEach(line => {
-        [line[0][0], line[0][1], line[1][0], line[1][1]] = [line[0][0] + gridOffsetX - (400 - line[0][0]) * (scale - 1), line[0][1] + gridOffsetY - (300 - line[0][1]) * (scale - 1), line[1][0] + gridOffsetX - (400 - line[1][0]) * (scale - 1), line[1][1] + gridOffsetY - (300 - line[1][1]) * (scale - 1)]
         drawLine(line, 'black', ctx);
       });
     }